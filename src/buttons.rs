@@ -0,0 +1,114 @@
+use bevy::prelude::*;
+use bevy_oxr::xr_input::Hand;
+
+use crate::constants::Finger;
+use crate::tracking::TrackedHands;
+
+// a box shaped button volume, pressed along its local +Y. the top face at `half_extents.y`
+// is the rest surface, a tip reaching the bottom face is fully pressed
+#[derive(Component, Debug, Clone, Copy)]
+pub struct PressButton {
+    pub half_extents: Vec3,
+}
+
+// sent every frame a fingertip is inside a button, `depth` in metres below the top face
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ButtonPress {
+    pub entity: Entity,
+    pub depth: f32,
+}
+
+// reads the tracked tips, the physics tips are pushed back out of a solid button and would never
+// report more than a sliver of depth
+pub fn press_buttons(
+    button_query: Query<(Entity, &GlobalTransform, &PressButton)>,
+    tracked_hands: Res<TrackedHands>,
+    mut press_events: EventWriter<ButtonPress>,
+) {
+    let tips: Vec<Vec3> = [Hand::Left, Hand::Right]
+        .into_iter()
+        .filter_map(|hand| tracked_hands.get(hand))
+        .flat_map(|joints| Finger::ALL.iter().map(|finger| *joints.joint(finger.tip())))
+        .filter(|tip| tip.position_tracked)
+        .map(|tip| tip.position)
+        .collect();
+
+    for (entity, button_transform, button) in button_query.iter() {
+        let to_local = button_transform.affine().inverse();
+
+        //deepest tip wins when several fingers are on the same button
+        let depth = tips
+            .iter()
+            .map(|tip| to_local.transform_point3(*tip))
+            .filter(|local| {
+                local.x.abs() <= button.half_extents.x
+                    && local.y.abs() <= button.half_extents.y
+                    && local.z.abs() <= button.half_extents.z
+            })
+            .map(|local| button.half_extents.y - local.y)
+            .max_by(|a, b| a.total_cmp(b));
+
+        if let Some(depth) = depth {
+            press_events.send(ButtonPress { entity, depth });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::constants::{get_default_hand, HandJoints, PhysicsHandBone};
+    use crate::pose::HandOrigin;
+
+    // right hand moved so its index tip sits on `position`
+    fn index_tip_at(position: Vec3) -> HandJoints {
+        let pose = get_default_hand(&Hand::Right);
+        let offset = position - pose.joint(PhysicsHandBone::IndexTip).position;
+        pose.transformed(&HandOrigin(Transform::from_translation(offset)))
+    }
+
+    #[test]
+    fn press_depth_grows_as_the_tip_goes_in() {
+        let mut world = World::new();
+        world.init_resource::<Events<ButtonPress>>();
+        //one finger wide, so only the index tip fits
+        let top = Vec3::new(0.0, -5.0, 0.0);
+        let button = world
+            .spawn((
+                GlobalTransform::from_translation(top - Vec3::Y * 0.01),
+                PressButton {
+                    half_extents: Vec3::new(0.005, 0.01, 0.005),
+                },
+            ))
+            .id();
+
+        let mut depths = Vec::new();
+        for step in 1..=4 {
+            world.insert_resource(TrackedHands {
+                left: None,
+                right: Some(index_tip_at(top - Vec3::Y * 0.004 * step as f32)),
+            });
+            world.run_system_once(press_buttons);
+
+            let presses: Vec<_> = world
+                .resource_mut::<Events<ButtonPress>>()
+                .drain()
+                .collect();
+            assert_eq!(presses.len(), 1);
+            assert_eq!(presses[0].entity, button);
+            depths.push(presses[0].depth);
+        }
+        assert!(depths.windows(2).all(|pair| pair[1] > pair[0]));
+        assert!((depths[3] - 0.016).abs() < 1e-5);
+
+        //a lost tip doesn't press anything
+        world.insert_resource(TrackedHands {
+            left: None,
+            right: Some(index_tip_at(top - Vec3::Y * 0.01).untracked()),
+        });
+        world.run_system_once(press_buttons);
+        assert!(world.resource::<Events<ButtonPress>>().is_empty());
+    }
+}
@@ -0,0 +1,248 @@
+use bevy::prelude::*;
+use bevy_oxr::xr_input::{
+    hands::common::{HandBoneRadius, HandsResource},
+    Hand,
+};
+
+use crate::constants::{get_default_hand, PhysicsHandBone};
+use crate::pose::get_tracked_hand_joints;
+
+// size of the user's hands relative to the default rest poses
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct HandScale {
+    pub left: f32,
+    pub right: f32,
+}
+
+impl Default for HandScale {
+    fn default() -> Self {
+        Self {
+            left: 1.0,
+            right: 1.0,
+        }
+    }
+}
+
+impl HandScale {
+    pub fn get(&self, hand: Hand) -> f32 {
+        match hand {
+            Hand::Left => self.left,
+            Hand::Right => self.right,
+        }
+    }
+
+    pub fn set(&mut self, hand: Hand, scale: f32) {
+        match hand {
+            Hand::Left => self.left = scale,
+            Hand::Right => self.right = scale,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CalibrationState {
+    pub samples: Vec<f32>,
+    pub complete: bool,
+}
+
+impl CalibrationState {
+    pub fn mean(&self) -> Option<f32> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        Some(self.samples.iter().sum::<f32>() / self.samples.len() as f32)
+    }
+
+    // feed one measured ratio, returns the scale once enough consecutive stable frames came in
+    pub fn push(&mut self, ratio: f32, required_frames: usize, tolerance: f32) -> Option<f32> {
+        if self.complete {
+            return None;
+        }
+
+        if let Some(mean) = self.mean() {
+            //a jump means the hand moved or tracking glitched, start over
+            if (ratio - mean).abs() > tolerance * mean {
+                self.samples.clear();
+            }
+        }
+        self.samples.push(ratio);
+
+        if self.samples.len() >= required_frames {
+            self.complete = true;
+            return self.mean();
+        }
+        None
+    }
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct ScaleCalibration {
+    pub required_frames: usize,
+    // allowed relative deviation from the running mean before samples are discarded
+    pub tolerance: f32,
+    pub left: CalibrationState,
+    pub right: CalibrationState,
+}
+
+impl Default for ScaleCalibration {
+    fn default() -> Self {
+        Self {
+            required_frames: 30,
+            tolerance: 0.05,
+            left: CalibrationState::default(),
+            right: CalibrationState::default(),
+        }
+    }
+}
+
+impl ScaleCalibration {
+    pub fn state_mut(&mut self, hand: Hand) -> &mut CalibrationState {
+        match hand {
+            Hand::Left => &mut self.left,
+            Hand::Right => &mut self.right,
+        }
+    }
+}
+
+// start a hand's calibration over, e.g. from a "recalibrate" button
+#[derive(Event, Debug, Clone, Copy)]
+pub struct RecalibrateRequest {
+    pub hand: Hand,
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct RecalibrationComplete {
+    pub hand: Hand,
+    pub scale: f32,
+}
+
+pub fn handle_recalibrate_requests(
+    mut requests: EventReader<RecalibrateRequest>,
+    mut calibration: ResMut<ScaleCalibration>,
+) {
+    for request in requests.read() {
+        *calibration.state_mut(request.hand) = CalibrationState::default();
+    }
+}
+
+pub fn calibrate_scale(
+    hands_res: Option<Res<HandsResource>>,
+    hand_query: Query<(&Transform, &HandBoneRadius, Option<&Visibility>), Without<PhysicsHandBone>>,
+    mut calibration: ResMut<ScaleCalibration>,
+    mut hand_scale: ResMut<HandScale>,
+    mut complete_events: EventWriter<RecalibrationComplete>,
+) {
+    let Some(hands_res) = hands_res else {
+        return;
+    };
+
+    let required_frames = calibration.required_frames;
+    let tolerance = calibration.tolerance;
+
+    for (hand, hand_res) in [(Hand::Left, hands_res.left), (Hand::Right, hands_res.right)] {
+        let Some(tracked) = get_tracked_hand_joints(&hand_res, &hand_query) else {
+            continue;
+        };
+        //a lost joint keeps wherever it was last seen, don't measure it
+        if !tracked.is_fully_tracked() {
+            continue;
+        }
+
+        let default_length = get_default_hand(&hand).total_bone_length();
+        if default_length <= f32::EPSILON {
+            continue;
+        }
+        let ratio = tracked.total_bone_length() / default_length;
+
+        if let Some(scale) = calibration
+            .state_mut(hand)
+            .push(ratio, required_frames, tolerance)
+        {
+            info!("calibrated {:?} hand scale to {}", hand, scale);
+            hand_scale.set(hand, scale);
+            complete_events.send(RecalibrationComplete { hand, scale });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::test_utils::{move_joint_entities, spawn_joint_entities};
+
+    fn calibration_world(left_scale: f32, right_scale: f32) -> World {
+        let mut world = World::new();
+        spawn_joint_entities(
+            &mut world,
+            &get_default_hand(&Hand::Left).scaled(left_scale),
+            &get_default_hand(&Hand::Right).scaled(right_scale),
+        );
+        world.insert_resource(ScaleCalibration {
+            required_frames: 3,
+            ..default()
+        });
+        world.init_resource::<HandScale>();
+        world.init_resource::<Events<RecalibrationComplete>>();
+        world
+    }
+
+    #[test]
+    fn scale_comes_from_measured_bone_lengths() {
+        let mut world = calibration_world(1.0, 1.1);
+
+        for _ in 0..3 {
+            world.run_system_once(calibrate_scale);
+        }
+
+        let hand_scale = world.resource::<HandScale>();
+        assert!((hand_scale.left - 1.0).abs() < 1e-4);
+        assert!((hand_scale.right - 1.1).abs() < 1e-4);
+        assert_eq!(world.resource::<Events<RecalibrationComplete>>().len(), 2);
+    }
+
+    #[test]
+    fn a_jump_in_the_measurement_starts_over() {
+        let mut state = CalibrationState::default();
+        assert_eq!(state.push(1.0, 3, 0.05), None);
+        assert_eq!(state.push(1.0, 3, 0.05), None);
+        assert_eq!(state.push(1.5, 3, 0.05), None);
+        assert_eq!(state.samples.len(), 1);
+        assert_eq!(state.push(1.5, 3, 0.05), None);
+        assert_eq!(state.push(1.5, 3, 0.05), Some(1.5));
+    }
+
+    #[test]
+    fn recalibrating_measures_the_hand_again() {
+        let mut world = calibration_world(1.0, 1.0);
+        world.init_resource::<Events<RecalibrateRequest>>();
+        let mut schedule = Schedule::default();
+        schedule.add_systems((handle_recalibrate_requests, calibrate_scale).chain());
+        for _ in 0..3 {
+            schedule.run(&mut world);
+        }
+        world
+            .resource_mut::<Events<RecalibrationComplete>>()
+            .clear();
+
+        //done calibrating, a bigger hand is ignored until asked to start over
+        let bigger = get_default_hand(&Hand::Right).scaled(1.2);
+        move_joint_entities(&mut world, Hand::Right, &bigger);
+        schedule.run(&mut world);
+        assert!((world.resource::<HandScale>().right - 1.0).abs() < 1e-4);
+
+        world.send_event(RecalibrateRequest { hand: Hand::Right });
+        for _ in 0..3 {
+            schedule.run(&mut world);
+        }
+
+        let events = world.resource::<Events<RecalibrationComplete>>();
+        let completed: Vec<_> = events.get_reader().read(events).copied().collect();
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed[0].hand, Hand::Right);
+        assert!((completed[0].scale - 1.2).abs() < 1e-4);
+        assert!((world.resource::<HandScale>().right - 1.2).abs() < 1e-4);
+        assert!((world.resource::<HandScale>().left - 1.0).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,79 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use crate::physics::PhysicsSchedule;
+
+// deterministic time for tests, built for unit tests and with the `test-clock` feature. time
+// stands still unless advanced with advance_time or run_fixed_steps, which step the app themselves
+pub struct TestClockPlugin;
+
+impl Plugin for TestClockPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::ZERO))
+            .init_resource::<TestClock>()
+            .add_systems(PhysicsSchedule, count_physics_steps);
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct TestClock {
+    // times the physics schedule has run since the plugin was added
+    pub physics_steps: u64,
+    started: bool,
+}
+
+fn count_physics_steps(mut clock: ResMut<TestClock>) {
+    clock.physics_steps += 1;
+}
+
+// runs one app update with exactly `duration` passing. the very first update of an app never
+// has any delta, so that one is run up front with nothing passing
+pub fn advance_time(app: &mut App, duration: Duration) {
+    if !app.world.resource::<TestClock>().started {
+        app.update();
+        app.world.resource_mut::<TestClock>().started = true;
+    }
+
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(duration));
+    app.update();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::ZERO));
+}
+
+// advances by one fixed timestep per update, `steps` times, and returns how many times the
+// physics schedule actually ran meanwhile (fewer while PhysicsStepControl is paused)
+pub fn run_fixed_steps(app: &mut App, steps: u32) -> u64 {
+    let timestep = app.world.resource::<Time<Fixed>>().timestep();
+    let before = app.world.resource::<TestClock>().physics_steps;
+
+    for _ in 0..steps {
+        advance_time(app, timestep);
+    }
+
+    app.world.resource::<TestClock>().physics_steps - before
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::HandPhysicsConfig;
+    use crate::test_utils::hand_app;
+
+    #[test]
+    fn five_fixed_steps_run_physics_five_times() {
+        let mut app = hand_app(HandPhysicsConfig::default());
+        let timestep = app.world.resource::<Time<Fixed>>().timestep();
+        let elapsed = app.world.resource::<Time<Virtual>>().elapsed();
+
+        assert_eq!(run_fixed_steps(&mut app, 5), 5);
+        assert_eq!(
+            app.world.resource::<Time<Virtual>>().elapsed() - elapsed,
+            timestep * 5
+        );
+
+        //standing still, nothing runs
+        let steps = app.world.resource::<TestClock>().physics_steps;
+        advance_time(&mut app, Duration::ZERO);
+        assert_eq!(app.world.resource::<TestClock>().physics_steps, steps);
+    }
+}
@@ -0,0 +1,1064 @@
+use bevy::prelude::*;
+use bevy_oxr::xr_input::Hand;
+use bevy_rapier3d::prelude::*;
+
+use crate::config::HandPhysicsConfig;
+use crate::constants::{
+    hand_membership, MatchingType, NameToHandJoint, PhysicsHandBone, PhysicsHandsResource,
+    FLOOR_GROUP,
+};
+use crate::grab::{Grabbable, Interactable, StuckTo};
+use crate::pose::{joint_radius, sanitize_orientation};
+use crate::targets::JointVisual;
+use crate::tracking::{TrackedHands, TrackedVelocities};
+
+// per hand collision switch, a disabled hand is purely cosmetic and generates no contacts
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct HandCollisionEnabled {
+    pub left: bool,
+    pub right: bool,
+}
+
+impl Default for HandCollisionEnabled {
+    fn default() -> Self {
+        Self {
+            left: true,
+            right: true,
+        }
+    }
+}
+
+impl HandCollisionEnabled {
+    pub fn get(&self, hand: Hand) -> bool {
+        match hand {
+            Hand::Left => self.left,
+            Hand::Right => self.right,
+        }
+    }
+}
+
+fn set_collider_active(commands: &mut Commands, entity: Entity, active: bool, disabled: bool) {
+    match (active, disabled) {
+        (true, true) => {
+            commands.entity(entity).remove::<ColliderDisabled>();
+        }
+        (false, false) => {
+            commands.entity(entity).insert(ColliderDisabled);
+        }
+        _ => {}
+    }
+}
+
+// turn a hand's bone colliders, thumb web and palm sensor on and off as a whole, from
+// HandCollisionEnabled and HandPhysicsConfig::lazy_colliders. bones with BoneDisabled stay off
+// either way
+pub fn update_collider_activation(
+    mut commands: Commands,
+    config: Res<HandPhysicsConfig>,
+    collision_enabled: Res<HandCollisionEnabled>,
+    bone_query: Query<(
+        Entity,
+        &PhysicsHandBone,
+        &Hand,
+        &GlobalTransform,
+        Has<ColliderDisabled>,
+        Has<BoneDisabled>,
+    )>,
+    interactable_query: Query<&GlobalTransform, Or<(With<Grabbable>, With<Interactable>)>>,
+    extra_query: Query<
+        (
+            Entity,
+            Option<&ThumbWeb>,
+            Option<&PalmSensor>,
+            Has<ColliderDisabled>,
+        ),
+        Or<(With<ThumbWeb>, With<PalmSensor>)>,
+    >,
+) {
+    for hand in [Hand::Left, Hand::Right] {
+        let palm = bone_query
+            .iter()
+            .find(|(_, bone, bone_hand, _, _, _)| {
+                **bone == PhysicsHandBone::Palm && **bone_hand == hand
+            })
+            .map(|(_, _, _, transform, _, _)| transform.translation());
+
+        let near_interactable = !config.lazy_colliders
+            || palm.is_some_and(|palm| {
+                interactable_query.iter().any(|transform| {
+                    transform.translation().distance(palm) < config.lazy_collider_radius
+                })
+            });
+        let enabled = collision_enabled.get(hand) && near_interactable;
+
+        for (entity, _, bone_hand, _, disabled, bone_disabled) in bone_query.iter() {
+            if *bone_hand != hand {
+                continue;
+            }
+
+            set_collider_active(&mut commands, entity, enabled && !bone_disabled, disabled);
+        }
+
+        //the web is part of the hand, the sensor only reports so it doesn't wait for an interactable
+        for (entity, web, sensor, disabled) in extra_query.iter() {
+            let active = match (web, sensor) {
+                (Some(web), _) if web.hand == hand => enabled,
+                (_, Some(sensor)) if sensor.hand == hand => collision_enabled.get(hand),
+                _ => continue,
+            };
+            set_collider_active(&mut commands, entity, active, disabled);
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct PalmSensor {
+    pub hand: Hand,
+}
+
+// an interactable came within HandPhysicsConfig::palm_sensor_radius of a palm
+#[derive(Event, Debug, Clone, Copy)]
+pub struct HandProximity {
+    pub hand: Hand,
+    pub entity: Entity,
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct HandProximityEnded {
+    pub hand: Hand,
+    pub entity: Entity,
+}
+
+pub fn spawn_palm_sensors(mut commands: Commands, config: Res<HandPhysicsConfig>) {
+    if !config.palm_sensor {
+        return;
+    }
+
+    for hand in [Hand::Left, Hand::Right] {
+        //never report the hands themselves
+        let mut filter = Group::ALL;
+        filter.remove(hand_membership(Hand::Left) | hand_membership(Hand::Right));
+
+        commands.spawn((
+            Name::new(format!("{:?} Palm Sensor", hand)),
+            TransformBundle::default(),
+            Collider::ball(config.palm_sensor_radius),
+            Sensor,
+            ActiveEvents::COLLISION_EVENTS,
+            ActiveCollisionTypes::all(),
+            CollisionGroups::new(filter, filter),
+            PalmSensor { hand },
+        ));
+    }
+}
+
+pub fn follow_palm_sensors(
+    physics_hands_res: Option<Res<PhysicsHandsResource>>,
+    palm_query: Query<&GlobalTransform, With<PhysicsHandBone>>,
+    mut sensor_query: Query<(&PalmSensor, &mut Transform)>,
+) {
+    let Some(physics_hands_res) = physics_hands_res else {
+        return;
+    };
+
+    for (sensor, mut transform) in sensor_query.iter_mut() {
+        let palm = physics_hands_res
+            .get(sensor.hand, PhysicsHandBone::Palm)
+            .and_then(|entity| palm_query.get(entity).ok());
+
+        if let Some(palm) = palm {
+            transform.translation = palm.translation();
+        }
+    }
+}
+
+pub fn report_hand_proximity(
+    mut collision_events: EventReader<CollisionEvent>,
+    sensor_query: Query<&PalmSensor>,
+    interactable_query: Query<(), Or<(With<Grabbable>, With<Interactable>)>>,
+    mut proximity_events: EventWriter<HandProximity>,
+    mut proximity_ended_events: EventWriter<HandProximityEnded>,
+) {
+    for event in collision_events.read() {
+        let (a, b, started) = match event {
+            CollisionEvent::Started(a, b, _) => (*a, *b, true),
+            CollisionEvent::Stopped(a, b, _) => (*a, *b, false),
+        };
+
+        let (sensor, other) = match (sensor_query.get(a), sensor_query.get(b)) {
+            (Ok(sensor), _) => (sensor, b),
+            (_, Ok(sensor)) => (sensor, a),
+            _ => continue,
+        };
+        if !interactable_query.contains(other) {
+            continue;
+        }
+
+        if started {
+            proximity_events.send(HandProximity {
+                hand: sensor.hand,
+                entity: other,
+            });
+        } else {
+            proximity_ended_events.send(HandProximityEnded {
+                hand: sensor.hand,
+                entity: other,
+            });
+        }
+    }
+}
+
+// radius a boosted collider had before it started growing
+#[derive(Component, Debug, Clone, Copy)]
+pub struct PunchBoost {
+    pub base_radius: f32,
+}
+
+// has to run after update_physics_hands, which rebuilds the capsules at their base radius. sized
+// from the tracked joint's speed rather than the body's, a bone held back by contact would never
+// grow right when the swing needs it
+pub fn boost_punch_colliders(
+    mut commands: Commands,
+    config: Res<HandPhysicsConfig>,
+    velocities: Res<TrackedVelocities>,
+    mut bone_query: Query<(
+        Entity,
+        &PhysicsHandBone,
+        &Hand,
+        &mut Collider,
+        Option<&PunchBoost>,
+    )>,
+) {
+    for (entity, bone, hand, mut collider, boost) in bone_query.iter_mut() {
+        if !bone.is_tip() && *bone != PhysicsHandBone::Palm {
+            continue;
+        }
+
+        let Some(radius) = bone_collider_radius(&collider) else {
+            continue;
+        };
+
+        //untracked joints report no velocity
+        let speed = velocities
+            .joint(*hand, *bone)
+            .map_or(0.0, |velocity| velocity.length());
+        let scale = (1.0 + config.punch_boost * speed).min(config.punch_boost_max.max(1.0));
+        let base_radius = boost.map_or(radius, |boost| boost.base_radius);
+
+        //slow again, put the original capsule back
+        if scale <= 1.0 + f32::EPSILON {
+            if boost.is_some() {
+                if (radius - base_radius).abs() > f32::EPSILON {
+                    if let Some(resized) = with_radius(&collider, base_radius) {
+                        *collider = resized;
+                    }
+                }
+                commands.entity(entity).remove::<PunchBoost>();
+            }
+            continue;
+        }
+
+        if boost.is_none() {
+            commands.entity(entity).insert(PunchBoost { base_radius });
+        }
+
+        //rebuilding allocates, skip changes too small to matter
+        let target = base_radius * scale;
+        if (radius - target).abs() > base_radius * 0.05 {
+            if let Some(resized) = with_radius(&collider, target) {
+                *collider = resized;
+            }
+        }
+    }
+}
+
+// radius of a bone's capsule or ball, None for shapes not sized by a single radius
+fn bone_collider_radius(collider: &Collider) -> Option<f32> {
+    collider
+        .as_capsule()
+        .map(|capsule| capsule.radius())
+        .or_else(|| collider.as_ball().map(|ball| ball.radius()))
+}
+
+// the same capsule or ball rebuilt at another radius
+fn with_radius(collider: &Collider, radius: f32) -> Option<Collider> {
+    if let Some(capsule) = collider.as_capsule() {
+        let segment = capsule.segment();
+        return Some(Collider::capsule(segment.a(), segment.b(), radius));
+    }
+    collider.as_ball().map(|_| Collider::ball(radius))
+}
+
+// collider spanning the thumb web of a hand, `length` is the segment its capsule was last built for
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ThumbWeb {
+    pub hand: Hand,
+    pub length: f32,
+}
+
+pub fn spawn_thumb_webs(mut commands: Commands, config: Res<HandPhysicsConfig>) {
+    if !config.thumb_web {
+        return;
+    }
+
+    for hand in [Hand::Left, Hand::Right] {
+        //same groups as the bones of the hand it belongs to
+        let membership = hand_membership(hand);
+        let mut filter = Group::ALL;
+        filter.remove(membership);
+        filter.remove(FLOOR_GROUP);
+
+        commands.spawn((
+            Name::new(format!("{:?} Thumb Web", hand)),
+            TransformBundle::default(),
+            RigidBody::KinematicPositionBased,
+            Collider::ball(
+                config.collider_radius(joint_radius(hand, PhysicsHandBone::ThumbProximal)),
+            ),
+            CollisionGroups::new(membership, filter),
+            config.solver_groups(),
+            ThumbWeb { hand, length: 0.0 },
+        ));
+    }
+}
+
+pub fn follow_thumb_webs(
+    config: Res<HandPhysicsConfig>,
+    physics_hands_res: Option<Res<PhysicsHandsResource>>,
+    bone_query: Query<&GlobalTransform, With<PhysicsHandBone>>,
+    mut web_query: Query<(&mut ThumbWeb, &mut Transform, &mut Collider)>,
+) {
+    let Some(physics_hands_res) = physics_hands_res else {
+        return;
+    };
+
+    for (mut web, mut transform, mut collider) in web_query.iter_mut() {
+        let position = |bone| {
+            physics_hands_res
+                .get(web.hand, bone)
+                .and_then(|entity| bone_query.get(entity).ok())
+                .map(|transform| transform.translation())
+        };
+
+        //compound fingers have no separate thumb proximal body, nothing to span
+        let (Some(thumb), Some(index_base), Some(index_knuckle)) = (
+            position(PhysicsHandBone::ThumbProximal),
+            position(PhysicsHandBone::IndexMetacarpal),
+            position(PhysicsHandBone::IndexProximal),
+        ) else {
+            continue;
+        };
+
+        let end = index_base.lerp(index_knuckle, 0.5);
+        let length = thumb.distance(end);
+        if length < config.min_collider_length {
+            continue;
+        }
+
+        //capsule down -Z from the thumb like the bones, only rebuilt once the span really changed
+        *transform = Transform::from_translation(thumb).looking_at(end, Vec3::Y);
+        if (web.length - length).abs() > config.collider_rebuild_threshold {
+            *collider = Collider::capsule(
+                Vec3::ZERO,
+                Vec3::new(0.0, 0.0, -length),
+                config.collider_radius(joint_radius(web.hand, PhysicsHandBone::ThumbProximal)),
+            );
+            web.length = length;
+        }
+    }
+}
+
+// on a palm bone driven as a box, the half extents its cuboid was last built with
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct PalmBox {
+    pub half_extents: Vec3,
+}
+
+pub fn drive_palm_boxes(
+    mut commands: Commands,
+    config: Res<HandPhysicsConfig>,
+    time: Res<Time>,
+    tracked_hands: Res<TrackedHands>,
+    physics_hands_res: Option<Res<PhysicsHandsResource>>,
+    mut palm_query: Query<
+        (
+            &mut Transform,
+            &mut Velocity,
+            &mut Collider,
+            Option<&mut PalmBox>,
+        ),
+        Without<BoneDisabled>,
+    >,
+) {
+    if !config.palm_box {
+        return;
+    }
+    let Some(physics_hands_res) = physics_hands_res else {
+        return;
+    };
+
+    for hand in [Hand::Left, Hand::Right] {
+        let (Some(joints), Some(entity)) = (
+            tracked_hands.get(hand),
+            physics_hands_res.get(hand, PhysicsHandBone::Palm),
+        ) else {
+            continue;
+        };
+        let Ok((mut transform, mut velocity, mut collider, palm_box)) = palm_query.get_mut(entity)
+        else {
+            continue;
+        };
+
+        let palm = joints.joint(PhysicsHandBone::Palm);
+        let Some(rotation) = sanitize_orientation(palm.orientation) else {
+            continue;
+        };
+        let rotation = config.corrected_orientation(rotation);
+
+        let half_extents = Vec3::new(
+            joints
+                .joint(PhysicsHandBone::IndexProximal)
+                .position
+                .distance(joints.joint(PhysicsHandBone::LittleProximal).position)
+                * 0.5,
+            config.palm_thickness * 0.5,
+            joints
+                .joint(PhysicsHandBone::Wrist)
+                .position
+                .distance(joints.joint(PhysicsHandBone::MiddleProximal).position)
+                * 0.5,
+        );
+
+        let needs_rebuild = palm_box.as_ref().map_or(true, |palm_box| {
+            (palm_box.half_extents - half_extents).abs().max_element()
+                > config.collider_rebuild_threshold
+        });
+        if needs_rebuild {
+            *collider = Collider::cuboid(half_extents.x, half_extents.y, half_extents.z);
+            match palm_box {
+                Some(mut palm_box) => palm_box.half_extents = half_extents,
+                None => {
+                    commands.entity(entity).insert(PalmBox { half_extents });
+                }
+            }
+        }
+
+        //jointed to the wrist, the joint carries it
+        if config.wrist_palm_joint {
+            continue;
+        }
+
+        drive_body(
+            config.matching_for(PhysicsHandBone::Palm),
+            &mut transform,
+            &mut velocity,
+            Transform::from_translation(palm.position).with_rotation(rotation),
+            time.delta_seconds(),
+        );
+    }
+}
+
+// move a body onto `target` the way its matching type says, like update_physics_hands does
+fn drive_body(
+    matching: MatchingType,
+    transform: &mut Transform,
+    velocity: &mut Velocity,
+    target: Transform,
+    delta: f32,
+) {
+    match matching {
+        MatchingType::PositionMatching => {
+            transform.translation = target.translation;
+            transform.rotation = target.rotation;
+        }
+        MatchingType::VelocityMatching => {
+            if delta <= 0.0 {
+                return;
+            }
+            velocity.linvel = (target.translation - transform.translation) / delta;
+            velocity.angvel =
+                (target.rotation * transform.rotation.inverse()).to_scaled_axis() / delta;
+        }
+    }
+}
+
+// on a palm held to its wrist by wrist_palm_joint
+#[derive(Component, Debug, Clone, Copy)]
+pub struct WristPalmJoint;
+
+pub fn drive_wrists(
+    config: Res<HandPhysicsConfig>,
+    time: Res<Time>,
+    tracked_hands: Res<TrackedHands>,
+    physics_hands_res: Option<Res<PhysicsHandsResource>>,
+    mut wrist_query: Query<
+        (&mut Transform, &mut Velocity),
+        (With<PhysicsHandBone>, Without<BoneDisabled>),
+    >,
+) {
+    if !config.wrist_palm_joint {
+        return;
+    }
+    let Some(physics_hands_res) = physics_hands_res else {
+        return;
+    };
+
+    for hand in [Hand::Left, Hand::Right] {
+        let (Some(joints), Some(entity)) = (
+            tracked_hands.get(hand),
+            physics_hands_res.get(hand, PhysicsHandBone::Wrist),
+        ) else {
+            continue;
+        };
+        let Ok((mut transform, mut velocity)) = wrist_query.get_mut(entity) else {
+            continue;
+        };
+
+        let wrist = joints.joint(PhysicsHandBone::Wrist);
+        let Some(rotation) = sanitize_orientation(wrist.orientation) else {
+            continue;
+        };
+
+        drive_body(
+            config.matching_for(PhysicsHandBone::Wrist),
+            &mut transform,
+            &mut velocity,
+            Transform::from_translation(wrist.position)
+                .with_rotation(config.corrected_orientation(rotation)),
+            time.delta_seconds(),
+        );
+    }
+}
+
+// joins each palm to its wrist where they currently are relative to each other, once
+pub fn joint_palms_to_wrists(
+    mut commands: Commands,
+    config: Res<HandPhysicsConfig>,
+    physics_hands_res: Option<Res<PhysicsHandsResource>>,
+    transform_query: Query<&Transform, With<PhysicsHandBone>>,
+    jointed_query: Query<(), With<WristPalmJoint>>,
+    stuck_query: Query<(), With<StuckTo>>,
+) {
+    let Some(physics_hands_res) = physics_hands_res else {
+        return;
+    };
+
+    for hand in [Hand::Left, Hand::Right] {
+        let (Some(wrist), Some(palm)) = (
+            physics_hands_res.get(hand, PhysicsHandBone::Wrist),
+            physics_hands_res.get(hand, PhysicsHandBone::Palm),
+        ) else {
+            continue;
+        };
+
+        //stuck to a grabbable the palm's joint is taken, it's jointed again once released
+        if stuck_query.contains(palm) {
+            continue;
+        }
+
+        let jointed = jointed_query.contains(palm);
+        if !config.wrist_palm_joint {
+            if jointed {
+                commands
+                    .entity(palm)
+                    .remove::<(ImpulseJoint, WristPalmJoint)>();
+            }
+            continue;
+        }
+        if jointed {
+            continue;
+        }
+
+        let (Ok(wrist_transform), Ok(palm_transform)) =
+            (transform_query.get(wrist), transform_query.get(palm))
+        else {
+            continue;
+        };
+
+        let wrist_inverse = wrist_transform.rotation.inverse();
+        let joint = FixedJointBuilder::new()
+            .local_anchor1(
+                wrist_inverse * (palm_transform.translation - wrist_transform.translation),
+            )
+            .local_basis1(wrist_inverse * palm_transform.rotation)
+            .local_anchor2(Vec3::ZERO);
+
+        commands
+            .entity(palm)
+            .insert((ImpulseJoint::new(wrist, joint), WristPalmJoint));
+    }
+}
+
+// tracked radius a bone's visuals were last sized for with live_joint_radius
+#[derive(Component, Debug, Clone, Copy)]
+pub struct LiveRadius(pub f32);
+
+// runs after update_physics_hands, which rebuilds the capsules whose length changed, and before
+// boost_punch_colliders so the boost grows from the live size
+pub fn apply_live_joint_radii(
+    mut commands: Commands,
+    config: Res<HandPhysicsConfig>,
+    tracked_hands: Res<TrackedHands>,
+    physics_hands_res: Option<Res<PhysicsHandsResource>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut bone_query: Query<
+        (
+            &mut Collider,
+            Option<&mut Handle<Mesh>>,
+            Option<&Children>,
+            Option<&LiveRadius>,
+        ),
+        (With<PhysicsHandBone>, Without<JointVisual>),
+    >,
+    mut visual_query: Query<(&mut Transform, &mut Handle<Mesh>), With<JointVisual>>,
+) {
+    if !config.live_joint_radius {
+        return;
+    }
+    let Some(physics_hands_res) = physics_hands_res else {
+        return;
+    };
+
+    for hand in [Hand::Left, Hand::Right] {
+        let Some(joints) = tracked_hands.get(hand) else {
+            continue;
+        };
+
+        for (index, joint) in joints.inner.iter().enumerate() {
+            let bone = NameToHandJoint::get_physics_bone_from_index(index);
+            let Some(entity) = physics_hands_res.get(hand, bone) else {
+                continue;
+            };
+            //untracked joints carry whatever radius the fallback pose has, keep the last live one
+            if !joint.position_tracked || joint.radius <= 0.0 {
+                continue;
+            }
+            let Ok((mut collider, mesh, children, live_radius)) = bone_query.get_mut(entity) else {
+                continue;
+            };
+
+            //compound finger bodies and palm boxes keep their shape
+            if let Some(radius) = bone_collider_radius(&collider) {
+                let target = config.collider_radius(joint.radius);
+                if (radius - target).abs() > 1e-4 {
+                    if let Some(resized) = with_radius(&collider, target) {
+                        *collider = resized;
+                    }
+                }
+            }
+
+            if live_radius.is_some_and(|live_radius| (live_radius.0 - joint.radius).abs() <= 1e-4) {
+                continue;
+            }
+            commands.entity(entity).insert(LiveRadius(joint.radius));
+
+            match mesh {
+                Some(mut mesh) => *mesh = meshes.add(Sphere::new(joint.radius)),
+                None => {
+                    for child in children.into_iter().flatten() {
+                        let Ok((mut transform, mut mesh)) = visual_query.get_mut(*child) else {
+                            continue;
+                        };
+                        //instanced visuals share a unit sphere and are sized by their scale
+                        if config.instanced_joints {
+                            transform.scale = Vec3::splat(joint.radius);
+                        } else {
+                            *mesh = meshes.add(Sphere::new(joint.radius));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+// takes a bone out of the hand at runtime, e.g. for a missing finger: no contacts, no driving and
+// hidden with HandPhysicsConfig::hide_disabled_bones. remove it to bring the bone back
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct BoneDisabled;
+
+// stops disabled bones where they are and hides or shows them as the marker comes and goes,
+// the colliders are handled by update_collider_activation
+pub fn apply_bone_disabled(
+    config: Res<HandPhysicsConfig>,
+    mut disabled_query: Query<(Ref<BoneDisabled>, &mut Velocity, Option<&mut Visibility>)>,
+    mut visibility_query: Query<&mut Visibility, (With<PhysicsHandBone>, Without<BoneDisabled>)>,
+    mut enabled: RemovedComponents<BoneDisabled>,
+) {
+    for (disabled, mut velocity, visibility) in disabled_query.iter_mut() {
+        if !disabled.is_added() {
+            continue;
+        }
+
+        *velocity = Velocity::zero();
+        if let (true, Some(mut visibility)) = (config.hide_disabled_bones, visibility) {
+            *visibility = Visibility::Hidden;
+        }
+    }
+
+    for entity in enabled.read() {
+        if let Ok(mut visibility) = visibility_query.get_mut(entity) {
+            *visibility = Visibility::Inherited;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::config::BoneBodyType;
+    use crate::constants::{get_default_hand, Finger, HAND_JOINT_COUNT};
+    use crate::pose::{rest_pose, HandOrigin, HandPlacement};
+    use crate::queries::PhysicsHands;
+    use crate::test_utils::{
+        bone_entity, bone_transform, drive, events, hand_app, spawn_ball, step,
+    };
+
+    // a palm and an index tip of `hand`, both on `position`
+    fn spawn_bones(world: &mut World, hand: Hand, position: Vec3) -> [Entity; 2] {
+        [PhysicsHandBone::Palm, PhysicsHandBone::IndexTip].map(|bone| {
+            world
+                .spawn((bone, hand, GlobalTransform::from_translation(position)))
+                .id()
+        })
+    }
+
+    fn collides(world: &World, bones: [Entity; 2]) -> [bool; 2] {
+        bones.map(|bone| !world.entity(bone).contains::<ColliderDisabled>())
+    }
+
+    #[test]
+    fn lazy_colliders_only_run_near_interactables() {
+        let mut world = World::new();
+        world.insert_resource(HandPhysicsConfig {
+            lazy_colliders: true,
+            lazy_collider_radius: 0.5,
+            ..default()
+        });
+        world.init_resource::<HandCollisionEnabled>();
+        let near = spawn_bones(&mut world, Hand::Left, Vec3::ZERO);
+        let far = spawn_bones(&mut world, Hand::Right, Vec3::X * 5.0);
+        world.spawn((Interactable, GlobalTransform::from_xyz(0.2, 0.0, 0.0)));
+
+        world.run_system_once(update_collider_activation);
+        assert_eq!(collides(&world, near), [true, true]);
+        assert_eq!(collides(&world, far), [false, false]);
+
+        //and back on as soon as something comes within reach
+        world.spawn((Grabbable, GlobalTransform::from_xyz(5.1, 0.0, 0.0)));
+        world.run_system_once(update_collider_activation);
+        assert_eq!(collides(&world, far), [true, true]);
+    }
+
+    #[test]
+    fn a_disabled_hand_makes_no_contacts() {
+        let mut app = hand_app(HandPhysicsConfig {
+            thumb_web: true,
+            palm_sensor: true,
+            ..default()
+        });
+        app.insert_resource(HandCollisionEnabled {
+            left: false,
+            right: true,
+        });
+
+        for hand in [Hand::Left, Hand::Right] {
+            let knuckle =
+                bone_transform(&app, hand, PhysicsHandBone::IndexIntermediate).translation;
+            spawn_ball(&mut app, knuckle, 0.01);
+        }
+        step(&mut app, 3);
+
+        let contacts = |app: &mut App, hand: Hand| {
+            app.world
+                .run_system_once(move |hands: PhysicsHands| hands.contacts(hand))
+        };
+        assert!(contacts(&mut app, Hand::Left).is_empty());
+        assert!(!contacts(&mut app, Hand::Right).is_empty());
+
+        //the extra colliders of the hand go with it
+        let mut extras = app.world.query::<(
+            Option<&ThumbWeb>,
+            Option<&PalmSensor>,
+            Has<ColliderDisabled>,
+        )>();
+        assert_eq!(
+            extras
+                .iter(&app.world)
+                .filter(|(web, sensor, _)| web.is_some() || sensor.is_some())
+                .count(),
+            4
+        );
+        for (web, sensor, disabled) in extras.iter(&app.world) {
+            let hand = match (web, sensor) {
+                (Some(web), _) => web.hand,
+                (_, Some(sensor)) => sensor.hand,
+                _ => continue,
+            };
+            assert_eq!(disabled, hand == Hand::Left);
+        }
+    }
+
+    #[test]
+    fn proximity_reports_interactables_within_the_radius() {
+        let mut app = hand_app(HandPhysicsConfig {
+            palm_sensor: true,
+            palm_sensor_radius: 0.15,
+            ..default()
+        });
+        let palm = bone_transform(&app, Hand::Right, PhysicsHandBone::Palm).translation;
+        let near = spawn_ball(&mut app, palm + Vec3::Y * 0.1, 0.01);
+        let far = spawn_ball(&mut app, palm + Vec3::Y * 0.5, 0.01);
+        app.world.entity_mut(near).insert(Interactable);
+        app.world.entity_mut(far).insert(Interactable);
+        //events only live for two frames, collect them as they come
+        let mut proximity = Vec::new();
+        for _ in 0..3 {
+            step(&mut app, 1);
+            proximity.extend(events::<HandProximity>(&app));
+        }
+
+        assert!(proximity
+            .iter()
+            .any(|event| event.hand == Hand::Right && event.entity == near));
+        assert!(!proximity.iter().any(|event| event.entity == far));
+    }
+
+    #[test]
+    fn fast_tracked_joints_grow_their_colliders() {
+        let mut world = World::new();
+        world.insert_resource(HandPhysicsConfig {
+            punch_boost: 0.2,
+            punch_boost_max: 3.0,
+            ..default()
+        });
+        let mut velocities = [Vec3::ZERO; HAND_JOINT_COUNT];
+        velocities[PhysicsHandBone::IndexTip.index()] = Vec3::new(0.0, 0.0, -5.0);
+        world.insert_resource(TrackedVelocities {
+            left: None,
+            right: Some(velocities),
+        });
+        let tip = world
+            .spawn((PhysicsHandBone::IndexTip, Hand::Right, Collider::ball(0.01)))
+            .id();
+        //no tracking for the left hand, so no boost however the body moves
+        let palm = world
+            .spawn((
+                PhysicsHandBone::Palm,
+                Hand::Left,
+                Collider::ball(0.02),
+                Velocity::linear(Vec3::X * 10.0),
+            ))
+            .id();
+        let radius = |world: &World, entity: Entity| {
+            bone_collider_radius(world.get::<Collider>(entity).unwrap()).unwrap()
+        };
+
+        world.run_system_once(boost_punch_colliders);
+        assert!((radius(&world, tip) - 0.02).abs() < 1e-5);
+        assert!((radius(&world, palm) - 0.02).abs() < 1e-6);
+        assert!(!world.entity(palm).contains::<PunchBoost>());
+
+        world.resource_mut::<TrackedVelocities>().right = Some([Vec3::ZERO; HAND_JOINT_COUNT]);
+        world.run_system_once(boost_punch_colliders);
+        assert!((radius(&world, tip) - 0.01).abs() < 1e-6);
+        assert!(!world.entity(tip).contains::<PunchBoost>());
+    }
+
+    #[test]
+    fn thumb_web_spans_the_thumb_and_index_base() {
+        let mut app = hand_app(HandPhysicsConfig::default());
+        step(&mut app, 1);
+        let mut web_query = app.world.query::<&ThumbWeb>();
+        assert_eq!(web_query.iter(&app.world).count(), 0);
+
+        let mut app = hand_app(HandPhysicsConfig {
+            thumb_web: true,
+            ..default()
+        });
+        step(&mut app, 2);
+
+        let mut web_query = app.world.query::<(&ThumbWeb, &Transform, &Collider)>();
+        let mut webs = 0;
+        for (web, transform, collider) in web_query.iter(&app.world) {
+            let position = |bone| bone_transform(&app, web.hand, bone).translation;
+            let thumb = position(PhysicsHandBone::ThumbProximal);
+            let index_base = position(PhysicsHandBone::IndexMetacarpal)
+                .lerp(position(PhysicsHandBone::IndexProximal), 0.5);
+
+            let capsule = collider.as_capsule().expect("the web is a capsule");
+            let start = transform.transform_point(capsule.segment().a());
+            let end = transform.transform_point(capsule.segment().b());
+            assert!(start.distance(thumb) < 1e-4);
+            assert!(end.distance(index_base) < 1e-3);
+            webs += 1;
+        }
+        assert_eq!(webs, 2);
+    }
+
+    #[test]
+    fn palm_box_is_a_cuboid_following_the_palm() {
+        let mut app = hand_app(HandPhysicsConfig {
+            palm_box: true,
+            ..default()
+        });
+        let raised = get_default_hand(&Hand::Right)
+            .transformed(&HandOrigin(Transform::from_xyz(0.0, 0.1, 0.0)));
+        drive(&mut app, Hand::Right, raised);
+        step(&mut app, 10);
+
+        let palm = bone_entity(&app, Hand::Right, PhysicsHandBone::Palm);
+        let cuboid = app
+            .world
+            .get::<Collider>(palm)
+            .unwrap()
+            .as_cuboid()
+            .expect("the palm is a box");
+        let spread = raised
+            .joint(PhysicsHandBone::IndexProximal)
+            .position
+            .distance(raised.joint(PhysicsHandBone::LittleProximal).position);
+        assert!((cuboid.half_extents().x - spread * 0.5).abs() < 1e-4);
+        assert!(app.world.entity(palm).contains::<PalmBox>());
+
+        let target = raised.joint(PhysicsHandBone::Palm);
+        let transform = bone_transform(&app, Hand::Right, PhysicsHandBone::Palm);
+        assert!(transform.translation.distance(target.position) < 0.005);
+        assert!(transform.rotation.angle_between(target.orientation) < 0.05);
+    }
+
+    #[test]
+    fn a_jointed_palm_moves_rigidly_with_the_wrist() {
+        let mut app = hand_app(HandPhysicsConfig {
+            body_type: BoneBodyType::Dynamic,
+            gravity_compensation: true,
+            wrist_palm_joint: true,
+            ..default()
+        });
+        let origin = *app.world.resource::<HandOrigin>();
+        let placement = *app.world.resource::<HandPlacement>();
+        let rest = rest_pose(Hand::Right, &origin, &placement);
+        drive(&mut app, Hand::Right, rest);
+        step(&mut app, 2);
+
+        let wrist = bone_entity(&app, Hand::Right, PhysicsHandBone::Wrist);
+        let palm = bone_entity(&app, Hand::Right, PhysicsHandBone::Palm);
+        assert!(app.world.entity(palm).contains::<WristPalmJoint>());
+        assert_eq!(
+            app.world
+                .get::<ImpulseJoint>(palm)
+                .map(|joint| joint.parent),
+            Some(wrist)
+        );
+        let palm_in_wrist = |app: &App| {
+            let wrist = bone_transform(app, Hand::Right, PhysicsHandBone::Wrist);
+            let palm = bone_transform(app, Hand::Right, PhysicsHandBone::Palm);
+            wrist.rotation.inverse() * (palm.translation - wrist.translation)
+        };
+        let before = palm_in_wrist(&app);
+        let palm_start = bone_transform(&app, Hand::Right, PhysicsHandBone::Palm).translation;
+
+        //the wrist target goes up and turns, the palm is only ever carried by the joint
+        let wrist_joint = rest.joint(PhysicsHandBone::Wrist);
+        let anchor = Transform::from_translation(wrist_joint.position + Vec3::Y * 0.1)
+            .with_rotation(Quat::from_rotation_y(0.3) * wrist_joint.orientation);
+        drive(&mut app, Hand::Right, rest.anchored_at_wrist(&anchor));
+        step(&mut app, 40);
+
+        let wrist_now = bone_transform(&app, Hand::Right, PhysicsHandBone::Wrist);
+        assert!(wrist_now.translation.distance(anchor.translation) < 0.01);
+        let palm_now = bone_transform(&app, Hand::Right, PhysicsHandBone::Palm).translation;
+        assert!(palm_now.y - palm_start.y > 0.05);
+        assert!(palm_in_wrist(&app).distance(before) < 0.005);
+    }
+
+    #[test]
+    fn live_radii_size_the_colliders_and_meshes() {
+        let config = HandPhysicsConfig {
+            live_joint_radius: true,
+            ..default()
+        };
+        let mut app = hand_app(config.clone());
+        let origin = *app.world.resource::<HandOrigin>();
+        let placement = *app.world.resource::<HandPlacement>();
+        let tip = bone_entity(&app, Hand::Right, PhysicsHandBone::IndexTip);
+
+        //the collider radius and the drawn sphere of the tip after tracking reports `radius`
+        let mut feed = |radius: f32| {
+            let mut pose = rest_pose(Hand::Right, &origin, &placement);
+            for joint in pose.inner.iter_mut() {
+                joint.radius = radius;
+            }
+            drive(&mut app, Hand::Right, pose);
+            step(&mut app, 2);
+
+            let collider = bone_collider_radius(app.world.get::<Collider>(tip).unwrap()).unwrap();
+            let handle = app.world.get::<Handle<Mesh>>(tip).unwrap();
+            let mesh = app.world.resource::<Assets<Mesh>>().get(handle).unwrap();
+            (collider, mesh.compute_aabb().unwrap().half_extents.x)
+        };
+
+        for radius in [0.02, 0.012] {
+            let (collider, mesh) = feed(radius);
+            assert!((collider - config.collider_radius(radius)).abs() < 1e-5);
+            assert!((mesh - radius).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn a_disabled_finger_neither_touches_nor_moves() {
+        let mut app = hand_app(HandPhysicsConfig {
+            hide_disabled_bones: true,
+            ..default()
+        });
+        let index: Vec<_> = Finger::Index
+            .bones()
+            .iter()
+            .map(|bone| bone_entity(&app, Hand::Right, *bone))
+            .collect();
+        for entity in index.iter() {
+            app.world.entity_mut(*entity).insert(BoneDisabled);
+        }
+
+        let knuckle = bone_transform(&app, Hand::Right, PhysicsHandBone::IndexIntermediate);
+        spawn_ball(&mut app, knuckle.translation, 0.01);
+        let middle_start =
+            bone_transform(&app, Hand::Right, PhysicsHandBone::MiddleProximal).translation;
+        let origin = *app.world.resource::<HandOrigin>();
+        let placement = *app.world.resource::<HandPlacement>();
+        let raised = rest_pose(Hand::Right, &origin, &placement)
+            .transformed(&HandOrigin(Transform::from_xyz(0.0, 0.1, 0.0)));
+        drive(&mut app, Hand::Right, raised);
+
+        let mut contacts = Vec::new();
+        for _ in 0..5 {
+            step(&mut app, 1);
+            contacts.extend(
+                app.world
+                    .run_system_once(|hands: PhysicsHands| hands.contacts(Hand::Right)),
+            );
+        }
+
+        assert!(!contacts.iter().any(|(bone, _)| bone
+            .decompose()
+            .is_some_and(|(finger, _)| finger == Finger::Index)));
+        for entity in index.iter() {
+            assert!(app.world.entity(*entity).contains::<ColliderDisabled>());
+            assert_eq!(
+                app.world.get::<Velocity>(*entity).unwrap().linvel,
+                Vec3::ZERO
+            );
+            assert_eq!(
+                app.world.get::<Visibility>(*entity),
+                Some(&Visibility::Hidden)
+            );
+        }
+        let stayed = bone_transform(&app, Hand::Right, PhysicsHandBone::IndexIntermediate);
+        assert!(stayed.translation.distance(knuckle.translation) < 1e-5);
+        //the rest of the hand still follows
+        let middle = bone_transform(&app, Hand::Right, PhysicsHandBone::MiddleProximal).translation;
+        assert!(middle.y - middle_start.y > 0.05);
+    }
+}
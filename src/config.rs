@@ -0,0 +1,454 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_rapier3d::prelude::{Group, RigidBody, SolverGroups};
+
+use crate::constants::{MatchingType, PhysicsHandBone};
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BoneBodyType {
+    Fixed,
+    // rapier integrates the velocity written by update_physics_hands itself
+    KinematicVelocityBased,
+    // fully simulated, pushed around by contacts and gravity like any other body
+    Dynamic,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BoneUpAxis {
+    // world +Y, bones roll against the hand as it rotates
+    World,
+    // +Y of the tracked palm, the back of the hand in openxr joint space
+    Palm,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TrackingLossBehavior {
+    // keep driving the bones from whatever the tracking source reports, lost joints included
+    FollowSource,
+    // snap back to the built in rest pose
+    Default,
+    // freeze on the last pose where every joint was tracked
+    HoldLastGoodPose,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RenderSource {
+    // joint spheres sit on the colliders, blended toward the target by `physics_blend`
+    Physics,
+    // joint spheres sit on the raw tracked joints, to see how far the physics hand lags behind
+    Tracked,
+}
+
+#[derive(Resource, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct HandPhysicsConfig {
+    pub body_type: BoneBodyType,
+    // parent each hand's bones under a HandRoot so the whole hand can be moved or hidden at once
+    pub spawn_hand_roots: bool,
+    // one rigid body per finger with a compound of its segment capsules instead of a body per joint,
+    // the fingers then move rigidly with their metacarpal
+    pub compound_fingers: bool,
+    // interpolate between the last two tracked poses across the fixed steps of a frame
+    pub interpolate_targets: bool,
+    // up reference for orienting bones along their segment
+    pub bone_up: BoneUpAxis,
+    // fingertips closer than this stop solving contacts against each other, 0 (the default)
+    // disables merging
+    pub fingertip_merge_distance: f32,
+    // what the bones are driven towards while a hand isn't fully tracked
+    pub tracking_loss: TrackingLossBehavior,
+    // some runtimes keep reporting a lost joint as tracked at its last position. live tracking
+    // always jitters a little, so with this set a joint that sits bit for bit still for this many
+    // frames counts as lost. 0 turns it off, a steady provider or a replayed pose holds still too
+    pub stale_joint_frames: u32,
+    // 0 passes the tracked wrist orientation through, towards 1 filters it more heavily. the rest
+    // of the hand turns with the filtered wrist
+    pub wrist_smoothing: f32,
+    // same filter per finger, indexed by `Finger as usize`. the thumb and index move quickest and
+    // want little of it, the ring and little fingers jitter more and can take more
+    pub finger_smoothing: [f32; 5],
+    // the rendered joint sphere uses the tracked joint radius as is, the collider radius is
+    // `joint.radius * render_to_collider_ratio - collider_shrink` (never below zero). the defaults
+    // keep the original `joint.radius / 2` colliders, a ratio of 1 makes it the mesh radius minus
+    // `collider_shrink`
+    pub render_to_collider_ratio: f32,
+    // extra absolute inset on top of the ratio, so touching a surface
+    // doesn't look like it happens a few millimetres early
+    pub collider_shrink: f32,
+    pub matching: MatchingType,
+    // per bone overrides of `matching`, e.g. position match the palm but velocity match the tips
+    pub bone_matching: HashMap<PhysicsHandBone, MatchingType>,
+    // solver groups of every bone, contacts outside the filter still show up as collision events
+    // but never push anything, so hands only affect the dynamics you opt in
+    #[cfg_attr(feature = "serde", serde(with = "group_bits"))]
+    pub solver_membership: Group,
+    #[cfg_attr(feature = "serde", serde(with = "group_bits"))]
+    pub solver_filter: Group,
+    // only keep a hand's colliders enabled while its palm is within `lazy_collider_radius`
+    // of a Grabbable or Interactable, the visuals stay either way
+    pub lazy_colliders: bool,
+    pub lazy_collider_radius: f32,
+    // bone length change in metres before its capsule collider gets rebuilt
+    pub collider_rebuild_threshold: f32,
+    // shortest capsule segment a bone collider is built with
+    pub min_collider_length: f32,
+    // draw every joint from one shared sphere mesh and one material per hand so they batch into
+    // instanced draws. the visuals then live on a child of each bone, and per bone material
+    // effects like the velocity tint don't apply
+    pub instanced_joints: bool,
+    // a sensor sphere following each palm that reports Grabbables and Interactables coming near,
+    // see HandProximity
+    pub palm_sensor: bool,
+    pub palm_sensor_radius: f32,
+    // with Dynamic bones, push each one up by its own weight so the targets aren't fighting gravity
+    pub gravity_compensation: bool,
+    // push targets ahead along their tracked velocity by this much to hide the tracking to physics
+    // latency, zero disables
+    pub latency_compensation: Duration,
+    // position matched bones take the tracked joint orientation as is instead of aiming
+    // down the segment, so they keep the joint's roll
+    pub match_tracked_orientation: bool,
+    // applied in joint space to every tracked orientation, for runtimes whose joint axes don't
+    // follow openxr's (-Z down the finger, +Y out of the back of the hand)
+    pub orientation_correction: Quat,
+    // where the joint spheres are drawn between the tracked target (0) and the simulated bone (1).
+    // below 1 the visuals move to a child of the bone like with `instanced_joints`
+    pub physics_blend: f32,
+    // fingertip and palm colliders grow by this fraction of their radius per m/s of speed, a crude
+    // swept volume so fast punches don't tunnel. 0 disables, `punch_boost_max` caps the growth
+    pub punch_boost: f32,
+    pub punch_boost_max: f32,
+    // capsule across the first web from the thumb proximal joint to the middle of the index
+    // metacarpal, so objects can't slip through the gap between thumb and index
+    pub thumb_web: bool,
+    // physics bones only spawn once a hand reports at least this many position tracked joints,
+    // half tracked first frames make bad colliders. 0 spawns at startup without waiting
+    pub min_tracked_joints: usize,
+    // the palm has no segment to build a capsule along, so without this it isn't driven and nothing
+    // stops objects passing through the middle of the hand. the box spans the knuckles sideways,
+    // the wrist to the middle knuckle lengthways and `palm_thickness` through the hand
+    pub palm_box: bool,
+    pub palm_thickness: f32,
+    // drive the wrist bone with the tracked wrist and hang the palm off it on a fixed joint, so the
+    // root of the hand moves as one rigid piece. the palm is then carried by the joint rather than
+    // driven itself, which needs Dynamic bones to have any effect
+    pub wrist_palm_joint: bool,
+    // size colliders and joint spheres from the radii the tracking source reports instead of the
+    // built in tables, following them as they change
+    pub live_joint_radius: bool,
+    // leave contacts between two bones of the same hand out of BoneContact and ContactReaction,
+    // so only what the hand touches in the world comes through
+    pub ignore_self_contacts: bool,
+    // also hide the visuals of bones carrying BoneDisabled
+    pub hide_disabled_bones: bool,
+    // what the joint spheres follow. anything but Physics puts the visuals on a child of the bone,
+    // so to flip it at runtime spawn with Tracked or a `physics_blend` below 1
+    pub render_source: RenderSource,
+}
+
+impl Default for HandPhysicsConfig {
+    fn default() -> Self {
+        Self {
+            body_type: BoneBodyType::Fixed,
+            spawn_hand_roots: false,
+            compound_fingers: false,
+            interpolate_targets: false,
+            bone_up: BoneUpAxis::Palm,
+            fingertip_merge_distance: 0.0,
+            tracking_loss: TrackingLossBehavior::FollowSource,
+            stale_joint_frames: 0,
+            wrist_smoothing: 0.0,
+            finger_smoothing: [0.0; 5],
+            render_to_collider_ratio: 0.5,
+            collider_shrink: 0.0,
+            matching: MatchingType::VelocityMatching,
+            bone_matching: HashMap::default(),
+            solver_membership: Group::ALL,
+            solver_filter: Group::ALL,
+            lazy_colliders: false,
+            lazy_collider_radius: 0.5,
+            collider_rebuild_threshold: 0.002,
+            min_collider_length: 0.005,
+            instanced_joints: false,
+            palm_sensor: false,
+            palm_sensor_radius: 0.2,
+            gravity_compensation: true,
+            latency_compensation: Duration::ZERO,
+            match_tracked_orientation: false,
+            orientation_correction: Quat::IDENTITY,
+            physics_blend: 1.0,
+            punch_boost: 0.0,
+            punch_boost_max: 3.0,
+            thumb_web: false,
+            min_tracked_joints: 0,
+            palm_box: false,
+            palm_thickness: 0.025,
+            wrist_palm_joint: false,
+            live_joint_radius: false,
+            ignore_self_contacts: false,
+            hide_disabled_bones: true,
+            render_source: RenderSource::Physics,
+        }
+    }
+}
+
+// rapier's Group only serializes with its own serde feature, store the raw bits instead
+#[cfg(feature = "serde")]
+mod group_bits {
+    use bevy_rapier3d::prelude::Group;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(group: &Group, serializer: S) -> Result<S::Ok, S::Error> {
+        group.bits().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Group, D::Error> {
+        Ok(Group::from_bits_truncate(u32::deserialize(deserializer)?))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl HandPhysicsConfig {
+    pub fn save_to(
+        &self,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let serialized = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        std::fs::write(path, serialized)?;
+        Ok(())
+    }
+
+    // fields missing from the file keep their defaults, so older tuning files still load
+    pub fn load_from(
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(ron::de::from_str(&contents)?)
+    }
+}
+
+impl HandPhysicsConfig {
+    pub fn rigid_body(&self) -> RigidBody {
+        match self.body_type {
+            BoneBodyType::Fixed => RigidBody::Fixed,
+            BoneBodyType::KinematicVelocityBased => RigidBody::KinematicVelocityBased,
+            BoneBodyType::Dynamic => RigidBody::Dynamic,
+        }
+    }
+
+    // the visuals can only move independently of the body when they live on a child of it
+    pub fn visuals_on_child(&self) -> bool {
+        self.instanced_joints
+            || self.physics_blend < 1.0
+            || self.render_source != RenderSource::Physics
+    }
+
+    pub fn collider_radius(&self, joint_radius: f32) -> f32 {
+        (joint_radius * self.render_to_collider_ratio - self.collider_shrink).max(0.0)
+    }
+
+    pub fn corrected_orientation(&self, orientation: Quat) -> Quat {
+        orientation * self.orientation_correction
+    }
+
+    pub fn solver_groups(&self) -> SolverGroups {
+        SolverGroups::new(self.solver_membership, self.solver_filter)
+    }
+
+    pub fn matching_for(&self, bone: PhysicsHandBone) -> MatchingType {
+        self.bone_matching
+            .get(&bone)
+            .copied()
+            .unwrap_or(self.matching)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collider_radius_defaults_to_half_the_joint_radius() {
+        let config = HandPhysicsConfig::default();
+        assert_eq!(config.collider_radius(0.02), 0.01);
+
+        let shrunk = HandPhysicsConfig {
+            collider_shrink: 0.004,
+            ..Default::default()
+        };
+        assert!((shrunk.collider_radius(0.02) - 0.006).abs() < 1e-6);
+        assert_eq!(shrunk.collider_radius(0.006), 0.0);
+    }
+
+    #[test]
+    fn spawned_colliders_sit_inside_the_joint_mesh() {
+        use bevy_oxr::xr_input::Hand;
+        use bevy_rapier3d::prelude::Collider;
+
+        use crate::test_utils::{bone_entity, hand_app};
+
+        // collider and drawn sphere radius of the spawned right index proximal
+        fn spawned_radii(config: HandPhysicsConfig) -> (f32, f32) {
+            let app = hand_app(config);
+            let bone = bone_entity(&app, Hand::Right, PhysicsHandBone::IndexProximal);
+            let collider = app
+                .world
+                .get::<Collider>(bone)
+                .unwrap()
+                .as_capsule()
+                .unwrap()
+                .radius();
+            let handle = app.world.get::<Handle<Mesh>>(bone).unwrap();
+            let mesh = app.world.resource::<Assets<Mesh>>().get(handle).unwrap();
+            (collider, mesh.compute_aabb().unwrap().half_extents.x)
+        }
+
+        let (collider, mesh) = spawned_radii(HandPhysicsConfig {
+            render_to_collider_ratio: 1.0,
+            collider_shrink: 0.002,
+            ..Default::default()
+        });
+        assert!((collider - (mesh - 0.002)).abs() < 1e-5);
+
+        //more shrink than there is radius leaves a point, not a negative radius
+        let (collider, _) = spawned_radii(HandPhysicsConfig {
+            render_to_collider_ratio: 1.0,
+            collider_shrink: 1.0,
+            ..Default::default()
+        });
+        assert_eq!(collider, 0.0);
+
+        let (collider, mesh) = spawned_radii(HandPhysicsConfig::default());
+        assert!((collider - mesh / 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn kinematic_bones_move_with_their_velocity() {
+        use bevy_oxr::xr_input::Hand;
+        use bevy_rapier3d::prelude::Velocity;
+
+        use crate::test_utils::{bone_entity, bone_transform, hand_app, step};
+
+        let mut app = hand_app(HandPhysicsConfig {
+            body_type: BoneBodyType::KinematicVelocityBased,
+            ..Default::default()
+        });
+        let bone = bone_entity(&app, Hand::Right, PhysicsHandBone::IndexProximal);
+        assert_eq!(
+            app.world.get::<RigidBody>(bone),
+            Some(&RigidBody::KinematicVelocityBased)
+        );
+
+        let start = bone_transform(&app, Hand::Right, PhysicsHandBone::IndexProximal).translation;
+        app.world.get_mut::<Velocity>(bone).unwrap().linvel = Vec3::X;
+        step(&mut app, 1);
+
+        let moved = bone_transform(&app, Hand::Right, PhysicsHandBone::IndexProximal).translation;
+        assert!(moved.x > start.x);
+    }
+
+    #[test]
+    fn bone_matching_overrides_the_global_matching() {
+        use bevy_oxr::xr_input::Hand;
+        use bevy_rapier3d::prelude::Velocity;
+
+        use crate::pose::{rest_pose, HandOrigin, HandPlacement};
+        use crate::test_utils::{bone_entity, bone_transform, drive, hand_app, step};
+
+        let mut config = HandPhysicsConfig::default();
+        config.bone_matching.insert(
+            PhysicsHandBone::IndexProximal,
+            MatchingType::PositionMatching,
+        );
+        assert_eq!(
+            config.matching_for(PhysicsHandBone::IndexProximal),
+            MatchingType::PositionMatching
+        );
+        assert_eq!(
+            config.matching_for(PhysicsHandBone::MiddleProximal),
+            MatchingType::VelocityMatching
+        );
+
+        let mut app = hand_app(config);
+        let origin = *app.world.resource::<HandOrigin>();
+        let placement = *app.world.resource::<HandPlacement>();
+        let raised = rest_pose(Hand::Right, &origin, &placement)
+            .transformed(&HandOrigin(Transform::from_xyz(0.0, 0.1, 0.0)));
+        drive(&mut app, Hand::Right, raised);
+        //the first update only initializes the bones
+        step(&mut app, 2);
+        let linvel = |bone| {
+            app.world
+                .get::<Velocity>(bone_entity(&app, Hand::Right, bone))
+                .unwrap()
+                .linvel
+        };
+
+        //the overridden bone is put straight on its target and never given a velocity
+        let overridden = bone_transform(&app, Hand::Right, PhysicsHandBone::IndexProximal);
+        let target = raised.joint(PhysicsHandBone::IndexProximal).position;
+        assert!(overridden.translation.distance(target) < 1e-5);
+        assert_eq!(linvel(PhysicsHandBone::IndexProximal), Vec3::ZERO);
+
+        //the rest still velocity match, a fixed body keeps chasing the raised target
+        assert!(linvel(PhysicsHandBone::MiddleProximal).y > 0.0);
+    }
+
+    #[test]
+    fn bones_spawn_with_the_configured_solver_groups() {
+        use bevy_oxr::xr_input::Hand;
+
+        use crate::test_utils::{bone_entity, hand_app};
+
+        let app = hand_app(HandPhysicsConfig {
+            solver_membership: Group::GROUP_3,
+            solver_filter: Group::GROUP_5,
+            ..Default::default()
+        });
+
+        let bone = bone_entity(&app, Hand::Left, PhysicsHandBone::IndexProximal);
+        let groups = app.world.get::<SolverGroups>(bone).unwrap();
+        assert_eq!(groups.memberships, Group::GROUP_3);
+        assert_eq!(groups.filters, Group::GROUP_5);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn tuned_config_round_trips_through_ron() {
+        let mut config = HandPhysicsConfig {
+            body_type: BoneBodyType::Dynamic,
+            wrist_smoothing: 0.4,
+            finger_smoothing: [0.1, 0.1, 0.2, 0.3, 0.3],
+            collider_shrink: 0.002,
+            solver_filter: Group::GROUP_2 | Group::GROUP_5,
+            latency_compensation: Duration::from_millis(30),
+            orientation_correction: Quat::from_rotation_z(0.3),
+            thumb_web: true,
+            ..Default::default()
+        };
+        config
+            .bone_matching
+            .insert(PhysicsHandBone::IndexTip, MatchingType::PositionMatching);
+
+        let path = std::env::temp_dir().join("xr_hand_config_round_trip.ron");
+        config.save_to(&path).unwrap();
+        let loaded = HandPhysicsConfig::load_from(&path).unwrap();
+        assert_eq!(loaded, config);
+
+        //an older file without the newer fields still loads
+        std::fs::write(&path, "(wrist_smoothing: 0.4)").unwrap();
+        let partial = HandPhysicsConfig::load_from(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(partial.wrist_smoothing, 0.4);
+        assert_eq!(
+            partial.collider_shrink,
+            HandPhysicsConfig::default().collider_shrink
+        );
+    }
+}
@@ -1,12 +1,47 @@
 
-use bevy::{asset::Assets, core::Name, ecs::{component::Component, entity::Entity, query::Without, system::{Commands, Query, Res, ResMut}}, log::info, math::{primitives::{Capsule3d, Sphere}, Quat, Vec3}, pbr::{PbrBundle, StandardMaterial}, prelude::SpatialBundle, render::{color::Color, mesh::{Mesh, Meshable}}, time::Time, transform::components::Transform};
-use bevy_rapier3d::{dynamics::{RigidBody, Velocity}, geometry::{Collider, CollisionGroups, Group}};
+use std::sync::OnceLock;
+
+use bevy::{asset::Assets, core::Name, hierarchy::BuildChildren, ecs::{component::Component, entity::Entity, query::Without, system::{Commands, Query, Res, ResMut}}, log::{error, trace_span}, math::{primitives::{Capsule3d, Sphere}, Quat, Vec3}, pbr::{PbrBundle, StandardMaterial}, prelude::SpatialBundle, render::{color::Color, mesh::{Mesh, Meshable}}, time::Time, transform::components::Transform};
+use bevy_rapier3d::{dynamics::Velocity, geometry::{Collider, CollisionGroups, Group}};
 
 use bevy_oxr::xr_input::{hands::{common::{HandBoneRadius, HandResource, HandsResource}, HandBone}, Hand};
 
+use crate::config::{BoneUpAxis, HandPhysicsConfig};
+use crate::colliders::BoneDisabled;
+use crate::debug::BoneTarget;
+use crate::targets::JointVisual;
+#[cfg(feature = "debug")]
+use crate::debug::HandDebugConfig;
+#[cfg(feature = "debug")]
+use bevy::log::{debug, trace};
+use crate::hand_root::spawn_hand_root;
+use crate::plugin::OnBoneSpawn;
+use crate::provider::{segment_end, HandTargetProvider, HandTargetSource, OxrHandTargets, OxrJointQuery};
+use crate::tips::TipBone;
+use crate::pose::{joint_radius, rest_pose, sanitize_orientation, HandOrigin, HandPlacement};
+use crate::tracking::TrackedHands;
+use crate::driven::DrivenPose;
+
 pub const FIXED_TIMESTEP: f32 = 1.0 / 60.0;
 
-#[derive(Component, PartialEq, Debug, Clone, Copy)]
+// joints per hand in the openxr hand tracking extension, every table in here assumes it
+pub const HAND_JOINT_COUNT: usize = 26;
+
+// bevy_oxr's bone list is indexed straight into our 26 entry tables,
+// a different length would silently misalign every bone
+pub fn check_joint_count(bone_count: usize) -> bool {
+    if bone_count != HAND_JOINT_COUNT {
+        error!(
+            "bevy_oxr reports {} hand bones but xr_hand expects {}, not spawning hands",
+            bone_count, HAND_JOINT_COUNT
+        );
+        return false;
+    }
+    true
+}
+
+#[derive(Component, PartialEq, Eq, Hash, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PhysicsHandBone {
     Palm,
     Wrist,
@@ -36,21 +71,244 @@ pub enum PhysicsHandBone {
     LittleTip,
 }
 
+impl PhysicsHandBone {
+    // position in the 26 joint arrays, the enum is declared in the same order
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    pub fn decompose(&self) -> Option<(Finger, Segment)> {
+        decompose(*self)
+    }
+
+    pub fn is_tip(&self) -> bool {
+        matches!(self.decompose(), Some((_, Segment::Tip)))
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum Segment {
+    Metacarpal,
+    Proximal,
+    Intermediate,
+    Distal,
+    Tip,
+}
+
+// palm and wrist aren't part of a finger
+pub fn decompose(bone: PhysicsHandBone) -> Option<(Finger, Segment)> {
+    match bone {
+        PhysicsHandBone::Palm => None,
+        PhysicsHandBone::Wrist => None,
+        PhysicsHandBone::ThumbMetacarpal => Some((Finger::Thumb, Segment::Metacarpal)),
+        PhysicsHandBone::ThumbProximal => Some((Finger::Thumb, Segment::Proximal)),
+        PhysicsHandBone::ThumbDistal => Some((Finger::Thumb, Segment::Distal)),
+        PhysicsHandBone::ThumbTip => Some((Finger::Thumb, Segment::Tip)),
+        PhysicsHandBone::IndexMetacarpal => Some((Finger::Index, Segment::Metacarpal)),
+        PhysicsHandBone::IndexProximal => Some((Finger::Index, Segment::Proximal)),
+        PhysicsHandBone::IndexIntermediate => Some((Finger::Index, Segment::Intermediate)),
+        PhysicsHandBone::IndexDistal => Some((Finger::Index, Segment::Distal)),
+        PhysicsHandBone::IndexTip => Some((Finger::Index, Segment::Tip)),
+        PhysicsHandBone::MiddleMetacarpal => Some((Finger::Middle, Segment::Metacarpal)),
+        PhysicsHandBone::MiddleProximal => Some((Finger::Middle, Segment::Proximal)),
+        PhysicsHandBone::MiddleIntermediate => Some((Finger::Middle, Segment::Intermediate)),
+        PhysicsHandBone::MiddleDistal => Some((Finger::Middle, Segment::Distal)),
+        PhysicsHandBone::MiddleTip => Some((Finger::Middle, Segment::Tip)),
+        PhysicsHandBone::RingMetacarpal => Some((Finger::Ring, Segment::Metacarpal)),
+        PhysicsHandBone::RingProximal => Some((Finger::Ring, Segment::Proximal)),
+        PhysicsHandBone::RingIntermediate => Some((Finger::Ring, Segment::Intermediate)),
+        PhysicsHandBone::RingDistal => Some((Finger::Ring, Segment::Distal)),
+        PhysicsHandBone::RingTip => Some((Finger::Ring, Segment::Tip)),
+        PhysicsHandBone::LittleMetacarpal => Some((Finger::Little, Segment::Metacarpal)),
+        PhysicsHandBone::LittleProximal => Some((Finger::Little, Segment::Proximal)),
+        PhysicsHandBone::LittleIntermediate => Some((Finger::Little, Segment::Intermediate)),
+        PhysicsHandBone::LittleDistal => Some((Finger::Little, Segment::Distal)),
+        PhysicsHandBone::LittleTip => Some((Finger::Little, Segment::Tip)),
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum Finger {
+    Thumb,
+    Index,
+    Middle,
+    Ring,
+    Little,
+}
+
+impl Finger {
+    pub const ALL: [Finger; 5] = [
+        Finger::Thumb,
+        Finger::Index,
+        Finger::Middle,
+        Finger::Ring,
+        Finger::Little,
+    ];
+
+    // metacarpal to tip, the thumb has no intermediate so it is one shorter
+    pub fn bones(&self) -> &'static [PhysicsHandBone] {
+        match self {
+            Finger::Thumb => &[
+                PhysicsHandBone::ThumbMetacarpal,
+                PhysicsHandBone::ThumbProximal,
+                PhysicsHandBone::ThumbDistal,
+                PhysicsHandBone::ThumbTip,
+            ],
+            Finger::Index => &[
+                PhysicsHandBone::IndexMetacarpal,
+                PhysicsHandBone::IndexProximal,
+                PhysicsHandBone::IndexIntermediate,
+                PhysicsHandBone::IndexDistal,
+                PhysicsHandBone::IndexTip,
+            ],
+            Finger::Middle => &[
+                PhysicsHandBone::MiddleMetacarpal,
+                PhysicsHandBone::MiddleProximal,
+                PhysicsHandBone::MiddleIntermediate,
+                PhysicsHandBone::MiddleDistal,
+                PhysicsHandBone::MiddleTip,
+            ],
+            Finger::Ring => &[
+                PhysicsHandBone::RingMetacarpal,
+                PhysicsHandBone::RingProximal,
+                PhysicsHandBone::RingIntermediate,
+                PhysicsHandBone::RingDistal,
+                PhysicsHandBone::RingTip,
+            ],
+            Finger::Little => &[
+                PhysicsHandBone::LittleMetacarpal,
+                PhysicsHandBone::LittleProximal,
+                PhysicsHandBone::LittleIntermediate,
+                PhysicsHandBone::LittleDistal,
+                PhysicsHandBone::LittleTip,
+            ],
+        }
+    }
+
+    pub fn tip(&self) -> PhysicsHandBone {
+        match self {
+            Finger::Thumb => PhysicsHandBone::ThumbTip,
+            Finger::Index => PhysicsHandBone::IndexTip,
+            Finger::Middle => PhysicsHandBone::MiddleTip,
+            Finger::Ring => PhysicsHandBone::RingTip,
+            Finger::Little => PhysicsHandBone::LittleTip,
+        }
+    }
+}
+
+// (parent, child) for every connection in the joint skeleton, a tree rooted at the wrist with
+// 25 edges covering all 26 joints. the palm and each metacarpal hang off the wrist, then every
+// finger runs metacarpal to tip. parents always come before their children
+pub const BONE_CONNECTIONS: &[(PhysicsHandBone, PhysicsHandBone)] = &[
+    (PhysicsHandBone::Wrist, PhysicsHandBone::Palm),
+    (PhysicsHandBone::Wrist, PhysicsHandBone::ThumbMetacarpal),
+    (PhysicsHandBone::ThumbMetacarpal, PhysicsHandBone::ThumbProximal),
+    (PhysicsHandBone::ThumbProximal, PhysicsHandBone::ThumbDistal),
+    (PhysicsHandBone::ThumbDistal, PhysicsHandBone::ThumbTip),
+    (PhysicsHandBone::Wrist, PhysicsHandBone::IndexMetacarpal),
+    (PhysicsHandBone::IndexMetacarpal, PhysicsHandBone::IndexProximal),
+    (PhysicsHandBone::IndexProximal, PhysicsHandBone::IndexIntermediate),
+    (PhysicsHandBone::IndexIntermediate, PhysicsHandBone::IndexDistal),
+    (PhysicsHandBone::IndexDistal, PhysicsHandBone::IndexTip),
+    (PhysicsHandBone::Wrist, PhysicsHandBone::MiddleMetacarpal),
+    (PhysicsHandBone::MiddleMetacarpal, PhysicsHandBone::MiddleProximal),
+    (PhysicsHandBone::MiddleProximal, PhysicsHandBone::MiddleIntermediate),
+    (PhysicsHandBone::MiddleIntermediate, PhysicsHandBone::MiddleDistal),
+    (PhysicsHandBone::MiddleDistal, PhysicsHandBone::MiddleTip),
+    (PhysicsHandBone::Wrist, PhysicsHandBone::RingMetacarpal),
+    (PhysicsHandBone::RingMetacarpal, PhysicsHandBone::RingProximal),
+    (PhysicsHandBone::RingProximal, PhysicsHandBone::RingIntermediate),
+    (PhysicsHandBone::RingIntermediate, PhysicsHandBone::RingDistal),
+    (PhysicsHandBone::RingDistal, PhysicsHandBone::RingTip),
+    (PhysicsHandBone::Wrist, PhysicsHandBone::LittleMetacarpal),
+    (PhysicsHandBone::LittleMetacarpal, PhysicsHandBone::LittleProximal),
+    (PhysicsHandBone::LittleProximal, PhysicsHandBone::LittleIntermediate),
+    (PhysicsHandBone::LittleIntermediate, PhysicsHandBone::LittleDistal),
+    (PhysicsHandBone::LittleDistal, PhysicsHandBone::LittleTip),
+];
+
+// collision memberships of the bones of each hand, and of the floor the hands never collide with
+pub const LEFT_HAND_GROUP: Group = Group::GROUP_1;
+pub const RIGHT_HAND_GROUP: Group = Group::GROUP_2;
+pub const FLOOR_GROUP: Group = Group::GROUP_3;
+
+pub fn hand_membership(hand: Hand) -> Group {
+    match hand {
+        Hand::Left => LEFT_HAND_GROUP,
+        Hand::Right => RIGHT_HAND_GROUP,
+    }
+}
+
+// physics bone entities per hand indexed like the joint arrays, filled in by spawn_physics_hands.
+// in compound finger mode only the metacarpal slot of each finger has a body
+#[derive(Resource, Debug, Clone, Default)]
+pub struct PhysicsHandsResource {
+    pub left: [Option<Entity>; HAND_JOINT_COUNT],
+    pub right: [Option<Entity>; HAND_JOINT_COUNT],
+}
+
+impl PhysicsHandsResource {
+    pub fn get(&self, hand: Hand, bone: PhysicsHandBone) -> Option<Entity> {
+        match hand {
+            Hand::Left => self.left[bone.index()],
+            Hand::Right => self.right[bone.index()],
+        }
+    }
+
+    pub fn set(&mut self, hand: Hand, bone: PhysicsHandBone, entity: Entity) {
+        match hand {
+            Hand::Left => self.left[bone.index()] = Some(entity),
+            Hand::Right => self.right[bone.index()] = Some(entity),
+        }
+    }
+
+    // every recorded bone entity of both hands, left first
+    pub fn all_bone_entities(&self) -> Vec<Entity> {
+        self.entities(Hand::Left)
+            .chain(self.entities(Hand::Right))
+            .collect()
+    }
+
+    pub fn entities(&self, hand: Hand) -> impl Iterator<Item = Entity> + '_ {
+        let slots = match hand {
+            Hand::Left => &self.left,
+            Hand::Right => &self.right,
+        };
+        slots.iter().flatten().copied()
+    }
+}
+
+// typed identity of a physics bone, use this for lookups rather than parsing the debug `Name`
+#[derive(Component, PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub struct HandId(pub Hand, pub PhysicsHandBone);
+
+// a whole finger simulated as one body with a compound collider, see HandPhysicsConfig::compound_fingers
+#[derive(Component, Debug, Clone, Copy)]
+pub struct FingerBody(pub Finger);
+
 #[derive(Component, PartialEq)]
 pub enum BoneInitState {
     True,
     False,
 }
 
+// bone length the current capsule collider was built for, None until the first build.
+// compound finger bodies don't get one, their collider is left alone
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct ColliderLength(pub Option<f32>);
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MatchingType {
     PositionMatching,
     VelocityMatching,
 }
 
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HandJoints {
-    pub inner: [HandJoint; 26],
+    pub inner: [HandJoint; HAND_JOINT_COUNT],
 }
 
 pub enum NameToHandJoint {
@@ -354,6 +612,15 @@ pub fn get_default_right_hand() -> HandJoints {
 
 
 
+pub fn get_default_hand(hand: &Hand) -> HandJoints {
+    let joints = match hand {
+        Hand::Left => get_default_left_hand(),
+        Hand::Right => get_default_right_hand(),
+    };
+    //the recorded orientations are only unit length to a few decimals
+    joints.sanitized().unwrap_or(joints)
+}
+
 pub fn get_default_left_hand() -> HandJoints {
     HandJoints {
         inner: [
@@ -388,289 +655,54 @@ pub fn get_default_left_hand() -> HandJoints {
 }
 
 
+// the recorded tables as they are, built once instead of on every lookup
+fn default_table(hand: &Hand) -> &'static HandJoints {
+    static LEFT: OnceLock<HandJoints> = OnceLock::new();
+    static RIGHT: OnceLock<HandJoints> = OnceLock::new();
+    match hand {
+        Hand::Left => LEFT.get_or_init(get_default_left_hand),
+        Hand::Right => RIGHT.get_or_init(get_default_right_hand),
+    }
+}
+
 impl NameToHandJoint {
+    //both hands read the same default arrays, so there is only one table per hand to keep right
     pub fn get_joint_data(&self, hand: &Hand) -> HandJoint {
-
-        if *hand == Hand::Left {
-            return self.get_left_hand_joint_data();
-        } else {
-            return self.get_right_hand_joint_data();
-        }
+        default_table(hand).inner[self.index()]
     }
 
-    fn get_right_hand_joint_data(&self) -> HandJoint {
-
+    // position in the 26 joint arrays
+    pub fn index(&self) -> usize {
         match self {
-            Self::Palm => HandJoint {
-                position: Vec3::new(0.11578956, 1.0322298, -0.07940306),
-                position_valid: true,
-                position_tracked: true,
-                orientation: Quat::from_xyzw(-0.16057923, -0.5977889, 0.76988935, -0.15534931),
-                orientation_valid: true,
-                orientation_tracked: true,
-                radius: 0.017204836,
-            },
-            Self::Wrist => HandJoint {
-                position: Vec3::new(0.110605344, 0.96545035, -0.06913033),
-                position_valid: true,
-                position_tracked: true,
-                orientation: Quat::from_xyzw(-0.16057923, -0.5977889, 0.76988935, -0.15534931),
-                orientation_valid: true,
-                orientation_tracked: true,
-                radius: 0.022538071,
-            },
-            Self::ThumbMetacarpal => HandJoint {
-                position: Vec3::new(0.13841398, 1.0021406, -0.052384503),
-                position_valid: true,
-                position_tracked: true,
-                orientation: Quat::from_xyzw(-0.73630154, -0.30601385, 0.56149095, -0.22123282),
-                orientation_valid: true,
-                orientation_tracked: true,
-                radius: 0.02035542,
-            },
-            Self::ThumbProximal => HandJoint {
-                position: Vec3::new(0.16202356, 1.0249985, -0.043111823),
-                position_valid: true,
-                position_tracked: true,
-                orientation: Quat::from_xyzw(-0.6474834, -0.40439665, 0.59034175, -0.26215592),
-                orientation_valid: true,
-                orientation_tracked: true,
-                radius: 0.012899493,
-            },
-            Self::ThumbDistal => HandJoint {
-                position: Vec3::new(0.18162939, 1.0539914, -0.03723684),
-                position_valid: true,
-                position_tracked: true,
-                orientation: Quat::from_xyzw(-0.75916475, -0.24931243, 0.58079475, -0.15553255),
-                orientation_valid: true,
-                orientation_tracked: true,
-                radius: 0.010259149,
-            },
-            Self::ThumbTip => HandJoint {
-                position: Vec3::new(0.20314479, 1.066816, -0.030817013),
-                position_valid: true,
-                position_tracked: true,
-                orientation: Quat::from_xyzw(-0.75916475, -0.24931243, 0.58079475, -0.15553255),
-                orientation_valid: true,
-                orientation_tracked: true,
-                radius: 0.009208953,
-            },
-            Self::IndexMetacarpal => HandJoint {
-                position: Vec3::new(0.12954025, 1.0039586, -0.061990373),
-                position_valid: true,
-                position_tracked: true,
-                orientation: Quat::from_xyzw(-0.16057923, -0.5977889, 0.76988935, -0.15534931),
-                orientation_valid: true,
-                orientation_tracked: true,
-                radius: 0.022293832,
-            },
-            Self::IndexProximal => HandJoint {
-                position: Vec3::new(0.13575831, 1.0662655, -0.0752951),
-                position_valid: true,
-                position_tracked: true,
-                orientation: Quat::from_xyzw(-0.17234212, -0.60941154, 0.7517424, -0.18384671),
-                orientation_valid: true,
-                orientation_tracked: true,
-                radius: 0.010812029,
-            },
-            Self::IndexIntermediate => HandJoint {
-                position: Vec3::new(0.13715388, 1.1052845, -0.08317496),
-                position_valid: true,
-                position_tracked: true,
-                orientation: Quat::from_xyzw(-0.13962409, -0.6614135, 0.71495426, -0.17854437),
-                orientation_valid: true,
-                orientation_tracked: true,
-                radius: 0.00896667,
-            },
-            Self::IndexDistal => HandJoint {
-                position: Vec3::new(0.13622141, 1.1306962, -0.0853719),
-                position_valid: true,
-                position_tracked: true,
-                orientation: Quat::from_xyzw(-0.11572188, -0.6341916, 0.7430719, -0.179595),
-                orientation_valid: true,
-                orientation_tracked: true,
-                radius: 0.008019494,
-            },
-            Self::IndexTip => HandJoint {
-                position: Vec3::new(0.13507375, 1.1536294, -0.09043077),
-                position_valid: true,
-                position_tracked: true,
-                orientation: Quat::from_xyzw(-0.11572188, -0.6341916, 0.7430719, -0.179595),
-                orientation_valid: true,
-                orientation_tracked: true,
-                radius: 0.006969299,
-            },
-            Self::MiddleMetacarpal => HandJoint {
-                position: Vec3::new(0.11431386, 1.0008209, -0.06930854),
-                position_valid: true,
-                position_tracked: true,
-                orientation: Quat::from_xyzw(-0.16057923, -0.5977889, 0.76988935, -0.15534931),
-                orientation_valid: true,
-                orientation_tracked: true,
-                radius: 0.022297699,
-            },
-            Self::MiddleProximal => HandJoint {
-                position: Vec3::new(0.11726526, 1.0636387, -0.08949757),
-                position_valid: true,
-                position_tracked: true,
-                orientation: Quat::from_xyzw(-0.10877958, -0.6516118, 0.7275088, -0.18520312),
-                orientation_valid: true,
-                orientation_tracked: true,
-                radius: 0.011734813,
-            },
-            Self::MiddleIntermediate => HandJoint {
-                position: Vec3::new(0.11351965, 1.1081975, -0.09522918),
-                position_valid: true,
-                position_tracked: true,
-                orientation: Quat::from_xyzw(-0.09748417, -0.65516704, 0.7271557, -0.18027195),
-                orientation_valid: true,
-                orientation_tracked: true,
-                radius: 0.008434071,
-            },
-            Self::MiddleDistal => HandJoint {
-                position: Vec3::new(0.11078716, 1.1367817, -0.09877359),
-                position_valid: true,
-                position_tracked: true,
-                orientation: Quat::from_xyzw(-0.07898912, -0.6207319, 0.7657275, -0.14870927),
-                orientation_valid: true,
-                orientation_tracked: true,
-                radius: 0.008012368,
-            },
-            Self::MiddleTip => HandJoint {
-                position: Vec3::new(0.109201774, 1.1620579, -0.10566684),
-                position_valid: true,
-                position_tracked: true,
-                orientation: Quat::from_xyzw(-0.07898912, -0.6207319, 0.7657275, -0.14870927),
-                orientation_valid: true,
-                orientation_tracked: true,
-                radius: 0.006962173,
-            },
-            Self::RingMetacarpal => HandJoint {
-                position: Vec3::new(0.0959552, 1.0016428, -0.07898356),
-                position_valid: true,
-                position_tracked: true,
-                orientation: Quat::from_xyzw(-0.16057923, -0.5977889, 0.76988935, -0.15534931),
-                orientation_valid: true,
-                orientation_tracked: true,
-                radius: 0.02004641,
-            },
-            Self::RingProximal => HandJoint {
-                position: Vec3::new(0.0968687, 1.056594, -0.09287319),
-                position_valid: true,
-                position_tracked: true,
-                orientation: Quat::from_xyzw(-0.061285853, -0.6445517, 0.7438205, -0.16591744),
-                orientation_valid: true,
-                orientation_tracked: true,
-                radius: 0.010420178,
-            },
-            Self::RingIntermediate => HandJoint {
-                position: Vec3::new(0.09184316, 1.0966957, -0.09949106),
-                position_valid: true,
-                position_tracked: true,
-                orientation: Quat::from_xyzw(-0.035476506, -0.65508807, 0.74103206, -0.14308292),
-                orientation_valid: true,
-                orientation_tracked: true,
-                radius: 0.007993739,
-            },
-            Self::RingDistal => HandJoint {
-                position: Vec3::new(0.088078886, 1.1240736, -0.103375815),
-                position_valid: true,
-                position_tracked: true,
-                orientation: Quat::from_xyzw(-0.05044055, -0.6750347, 0.7261634, -0.12029514),
-                orientation_valid: true,
-                orientation_tracked: true,
-                radius: 0.0075940522,
-            },
-            Self::RingTip => HandJoint {
-                position: Vec3::new(0.08594976, 1.1492996, -0.10720982),
-                position_valid: true,
-                position_tracked: true,
-                orientation: Quat::from_xyzw(-0.05044055, -0.6750347, 0.7261634, -0.12029514),
-                orientation_valid: true,
-                orientation_tracked: true,
-                radius: 0.006543857,
-            },
-            Self::LittleMetacarpal => HandJoint {
-                position: Vec3::new(0.08679972, 1.0013778, -0.07933965),
-                position_valid: true,
-                position_tracked: true,
-                orientation: Quat::from_xyzw(0.079209626, -0.6042261, 0.78903735, -0.07782571),
-                orientation_valid: true,
-                orientation_tracked: true,
-                radius: 0.018996214,
-            },
-            Self::LittleProximal => HandJoint {
-                position: Vec3::new(0.076300375, 1.0465008, -0.091672905),
-                position_valid: true,
-                position_tracked: true,
-                orientation: Quat::from_xyzw(0.0013647676, -0.611447, 0.7800056, -0.13312519),
-                orientation_valid: true,
-                orientation_tracked: true,
-                radius: 0.008909174,
-            },
-            Self::LittleIntermediate => HandJoint {
-                position: Vec3::new(0.07097942, 1.0772631, -0.09981148),
-                position_valid: true,
-                position_tracked: true,
-                orientation: Quat::from_xyzw(0.06511599, -0.6392353, 0.7561073, -0.12425861),
-                orientation_valid: true,
-                orientation_tracked: true,
-                radius: 0.007103722,
-            },
-            Self::LittleDistal => HandJoint {
-                position: Vec3::new(0.065490335, 1.0975376, -0.103528954),
-                position_valid: true,
-                position_tracked: true,
-                orientation: Quat::from_xyzw(0.029709637, -0.6588042, 0.746072, -0.09203919),
-                orientation_valid: true,
-                orientation_tracked: true,
-                radius: 0.006748536,
-            },
-            Self::LittleTip => HandJoint {
-                position: Vec3::new(0.062057115, 1.1199425, -0.1077688),
-                position_valid: true,
-                position_tracked: true,
-                orientation: Quat::from_xyzw(0.029709637, -0.6588042, 0.746072, -0.09203919),
-                orientation_valid: true,
-                orientation_tracked: true,
-                radius: 0.005698341,
-            },
+            NameToHandJoint::Palm => 0,
+            NameToHandJoint::Wrist => 1,
+            NameToHandJoint::ThumbMetacarpal => 2,
+            NameToHandJoint::ThumbProximal => 3,
+            NameToHandJoint::ThumbDistal => 4,
+            NameToHandJoint::ThumbTip => 5,
+            NameToHandJoint::IndexMetacarpal => 6,
+            NameToHandJoint::IndexProximal => 7,
+            NameToHandJoint::IndexIntermediate => 8,
+            NameToHandJoint::IndexDistal => 9,
+            NameToHandJoint::IndexTip => 10,
+            NameToHandJoint::MiddleMetacarpal => 11,
+            NameToHandJoint::MiddleProximal => 12,
+            NameToHandJoint::MiddleIntermediate => 13,
+            NameToHandJoint::MiddleDistal => 14,
+            NameToHandJoint::MiddleTip => 15,
+            NameToHandJoint::RingMetacarpal => 16,
+            NameToHandJoint::RingProximal => 17,
+            NameToHandJoint::RingIntermediate => 18,
+            NameToHandJoint::RingDistal => 19,
+            NameToHandJoint::RingTip => 20,
+            NameToHandJoint::LittleMetacarpal => 21,
+            NameToHandJoint::LittleProximal => 22,
+            NameToHandJoint::LittleIntermediate => 23,
+            NameToHandJoint::LittleDistal => 24,
+            NameToHandJoint::LittleTip => 25,
         }
     }
 
-    fn get_left_hand_joint_data(&self) -> HandJoint {
-
-        let hand_joints = get_default_left_hand();
-
-        match self {
-            NameToHandJoint::Palm => hand_joints.inner[0],
-            NameToHandJoint::Wrist => hand_joints.inner[1],
-            NameToHandJoint::ThumbMetacarpal => hand_joints.inner[2],
-            NameToHandJoint::ThumbProximal => hand_joints.inner[3],
-            NameToHandJoint::ThumbDistal => hand_joints.inner[4],
-            NameToHandJoint::ThumbTip => hand_joints.inner[5],
-            NameToHandJoint::IndexMetacarpal => hand_joints.inner[6],
-            NameToHandJoint::IndexProximal => hand_joints.inner[7],
-            NameToHandJoint::IndexIntermediate => hand_joints.inner[8],
-            NameToHandJoint::IndexDistal => hand_joints.inner[9],
-            NameToHandJoint::IndexTip => hand_joints.inner[10],
-            NameToHandJoint::MiddleMetacarpal => hand_joints.inner[11],
-            NameToHandJoint::MiddleProximal => hand_joints.inner[12],
-            NameToHandJoint::MiddleIntermediate => hand_joints.inner[13],
-            NameToHandJoint::MiddleDistal => hand_joints.inner[14],
-            NameToHandJoint::MiddleTip => hand_joints.inner[15],
-            NameToHandJoint::RingMetacarpal => hand_joints.inner[16],
-            NameToHandJoint::RingProximal => hand_joints.inner[17],
-            NameToHandJoint::RingIntermediate => hand_joints.inner[18],
-            NameToHandJoint::RingDistal => hand_joints.inner[19],
-            NameToHandJoint::RingTip => hand_joints.inner[20],
-            NameToHandJoint::LittleMetacarpal => hand_joints.inner[21],
-            NameToHandJoint::LittleProximal => hand_joints.inner[22],
-            NameToHandJoint::LittleIntermediate => hand_joints.inner[23],
-            NameToHandJoint::LittleDistal => hand_joints.inner[24],
-            NameToHandJoint::LittleTip => hand_joints.inner[25],
-        }
-    }
     pub fn get_physics_bone_from_index(index: usize) -> PhysicsHandBone {
         match index {
             0 => PhysicsHandBone::Palm,
@@ -705,6 +737,7 @@ impl NameToHandJoint {
 }
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HandJoint {
     pub position: Vec3,
     pub position_valid: bool,
@@ -834,6 +867,37 @@ pub fn get_start_and_end_joints(
 }
 
 
+pub fn get_hand_resource_entity(hand_res: &HandResource, bone: PhysicsHandBone) -> Entity {
+    match bone {
+        PhysicsHandBone::Palm => hand_res.palm,
+        PhysicsHandBone::Wrist => hand_res.wrist,
+        PhysicsHandBone::ThumbMetacarpal => hand_res.thumb.metacarpal,
+        PhysicsHandBone::ThumbProximal => hand_res.thumb.proximal,
+        PhysicsHandBone::ThumbDistal => hand_res.thumb.distal,
+        PhysicsHandBone::ThumbTip => hand_res.thumb.tip,
+        PhysicsHandBone::IndexMetacarpal => hand_res.index.metacarpal,
+        PhysicsHandBone::IndexProximal => hand_res.index.proximal,
+        PhysicsHandBone::IndexIntermediate => hand_res.index.intermediate,
+        PhysicsHandBone::IndexDistal => hand_res.index.distal,
+        PhysicsHandBone::IndexTip => hand_res.index.tip,
+        PhysicsHandBone::MiddleMetacarpal => hand_res.middle.metacarpal,
+        PhysicsHandBone::MiddleProximal => hand_res.middle.proximal,
+        PhysicsHandBone::MiddleIntermediate => hand_res.middle.intermediate,
+        PhysicsHandBone::MiddleDistal => hand_res.middle.distal,
+        PhysicsHandBone::MiddleTip => hand_res.middle.tip,
+        PhysicsHandBone::RingMetacarpal => hand_res.ring.metacarpal,
+        PhysicsHandBone::RingProximal => hand_res.ring.proximal,
+        PhysicsHandBone::RingIntermediate => hand_res.ring.intermediate,
+        PhysicsHandBone::RingDistal => hand_res.ring.distal,
+        PhysicsHandBone::RingTip => hand_res.ring.tip,
+        PhysicsHandBone::LittleMetacarpal => hand_res.little.metacarpal,
+        PhysicsHandBone::LittleProximal => hand_res.little.proximal,
+        PhysicsHandBone::LittleIntermediate => hand_res.little.intermediate,
+        PhysicsHandBone::LittleDistal => hand_res.little.distal,
+        PhysicsHandBone::LittleTip => hand_res.little.tip,
+    }
+}
+
 pub fn get_start_and_end_entities(
     hand_res: HandResource,
     bone: &PhysicsHandBone,
@@ -905,10 +969,16 @@ pub fn spawn_hand_entities(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    hand_origin: Res<HandOrigin>,
+    placement: Res<HandPlacement>,
 ) {
     let hands = [Hand::Left, Hand::Right];
     let bones = HandBone::get_all_bones();
 
+    if !check_joint_count(bones.len()) {
+        return;
+    }
+
     // let right_hand = get_default_right_hand();
     // let left_hand = get_default_left_hand();
 
@@ -916,6 +986,7 @@ pub fn spawn_hand_entities(
     //hand resource
     let mut hand_resource = HandsResource { ..Default::default() };
     for hand in hands.iter() {
+        let hand_pose = rest_pose(*hand, &hand_origin, &placement);
 
         for bone in bones.iter() {
 
@@ -929,7 +1000,9 @@ pub fn spawn_hand_entities(
                 continue;
             }
 
-            let (joint_one, joint_two) = joints_opt.unwrap();
+            //every bone runs from its own joint to the next one in the chain
+            let joint_one = hand_pose.inner[physics_bone.index()];
+            let joint_two = hand_pose.inner[physics_bone.index() + 1];
 
 
             let direction = joint_two.position - joint_one.position;
@@ -941,6 +1014,7 @@ pub fn spawn_hand_entities(
 
             let boneid = commands
                 .spawn((
+                    //cosmetic, for inspectors and logs only
                     Name::new(format!("{:?} {:?}", hand, bone)),
                     // SpatialBundle::default(),
                     PbrBundle {
@@ -1004,12 +1078,22 @@ pub fn spawn_hand_entities(
 
 pub fn spawn_physics_hands(
     mut commands: Commands,
-    hands_res: Res<HandsResource>,
+    hands_res: Option<Res<HandsResource>>,
     hand_query: Query<(&Transform, &HandBone, &Hand), Without<PhysicsHandBone>>,
     mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    hand_origin: Res<HandOrigin>,
+    placement: Res<HandPlacement>,
+    config: Res<HandPhysicsConfig>,
+    #[cfg(feature = "debug")] debug_config: Res<HandDebugConfig>,
+    on_bone_spawn: Res<OnBoneSpawn>,
 
 ) {
+    //same guard as spawn_hand_entities, everything below indexes our tables in bevy_oxr's bone order
+    if !check_joint_count(HandBone::get_all_bones().len()) {
+        return;
+    }
+
     let hands = [Hand::Left, Hand::Right];
     let bones = [
         PhysicsHandBone::Palm,
@@ -1040,20 +1124,20 @@ pub fn spawn_physics_hands(
         PhysicsHandBone::LittleTip,
     ];
     let radius = 0.010;
-    let left_hand_membership_group = Group::GROUP_1;
-    let right_hand_membership_group = Group::GROUP_2;
-    let floor_membership = Group::GROUP_3;
+    let left_hand_membership_group = LEFT_HAND_GROUP;
+    let right_hand_membership_group = RIGHT_HAND_GROUP;
+    let floor_membership = FLOOR_GROUP;
+    let mut physics_hands_res = PhysicsHandsResource::default();
+    //one unit sphere for every joint, entities sharing a mesh and material get drawn instanced
+    let joint_mesh = config
+        .instanced_joints
+        .then(|| meshes.add(Sphere::new(1.0)));
 
     // let hand_joints = get_default_right_hand();
 
-    println!("hand_query {:?}", hand_query);
-
     for hand in hands.iter() {
 
-        let hand_joints = match hand {
-            Hand::Left => get_default_left_hand(),
-            Hand::Right => get_default_right_hand(),
-        };
+        let hand_joints = rest_pose(*hand, &hand_origin, &placement);
 
         let hand_membership = match hand {
             Hand::Left => left_hand_membership_group,
@@ -1065,48 +1149,202 @@ pub fn spawn_physics_hands(
 
         // Collider::compound()
 
-        for joint in hand_joints.inner.iter() {
+        let hand_root = if config.spawn_hand_roots {
+            Some(spawn_hand_root(&mut commands, *hand))
+        } else {
+            None
+        };
 
+        let color = match hand {
+            Hand::Left => Color::rgb(0.8, 0.7, 0.6),
+            Hand::Right => Color::rgb(0.6, 0.7, 0.8),
+        };
+        let hand_material = config.instanced_joints.then(|| materials.add(color));
 
-            let color = match hand {
-                Hand::Left => Color::rgb(0.8, 0.7, 0.6),
-                Hand::Right => Color::rgb(0.6, 0.7, 0.8),
-            };
+        for (index, joint) in hand_joints.inner.iter().enumerate() {
 
-            //spawn the thing
-            commands.spawn((
-                // SpatialBundle::default(),
-                PbrBundle {
-                    mesh: meshes.add(Sphere::new(joint.radius)),
-                    material: materials.add(color),
-                    transform: Transform {
-                        translation: joint.position,
-                        rotation: joint.orientation,
-                        ..Default::default()
-                    },
-                    ..Default::default()
-                },
-                Collider::capsule(
-                    Vec3 {
-                        x: 0.0,
-                        y: -0.0575,
-                        z: 0.0,
-                    },
+
+            let physics_bone = NameToHandJoint::get_physics_bone_from_index(index);
+
+            //in compound mode the finger joints get folded into one body per finger below
+            if config.compound_fingers && physics_bone.decompose().is_some() {
+                continue;
+            }
+
+            #[cfg(feature = "debug")]
+            if debug_config.enabled {
+                debug!(
+                    "spawning {:?} {:?} at {:?}, mesh radius {}, collider radius {}",
+                    hand,
+                    physics_bone,
+                    joint.position,
+                    joint.radius,
+                    config.collider_radius(joint.radius)
+                );
+            }
+
+            //capsule down -Z to the child joint like update_physics_hands builds it, the tips and the
+            //palm and wrist have no child and get a ball
+            let segment_length = get_start_and_end_joints(&physics_bone, hand)
+                .map(|(start, end)| start.position.distance(end.position).max(config.min_collider_length));
+            let collider = match segment_length {
+                Some(length) => Collider::capsule(
+                    Vec3::ZERO,
                     Vec3 {
                         x: 0.0,
-                        y: 0.0575,
-                        z: 0.0,
+                        y: 0.0,
+                        z: -length,
                     },
-                    joint.radius / 2.0,
+                    config.collider_radius(joint.radius),
                 ),
-                RigidBody::Fixed,
+                None => Collider::ball(config.collider_radius(joint.radius)),
+            };
+
+            //spawn the thing
+            let mut bone_commands = commands.spawn((
+                SpatialBundle::from_transform(Transform {
+                    translation: joint.position,
+                    rotation: joint.orientation,
+                    ..Default::default()
+                }),
+                collider,
+                config.rigid_body(),
                 Velocity::default(),
                 CollisionGroups::new(hand_membership, hand_filter),
-                // SolverGroups::new(self_group, interaction_group),
-                PhysicsHandBone::Palm,
+                config.solver_groups(),
+                physics_bone,
                 *hand,
-                
+                HandId(*hand, physics_bone),
+                BoneTarget::default(),
+                BoneInitState::False,
+                ColliderLength(segment_length),
             ));
+
+            match (&joint_mesh, &hand_material) {
+                //scaled on a child, scaling the bone itself would scale its collider too
+                (Some(joint_mesh), Some(hand_material)) => {
+                    bone_commands.with_children(|parent| {
+                        parent.spawn((
+                            PbrBundle {
+                                mesh: joint_mesh.clone(),
+                                material: hand_material.clone(),
+                                transform: Transform::from_scale(Vec3::splat(joint.radius)),
+                                ..Default::default()
+                            },
+                            JointVisual,
+                        ));
+                    });
+                }
+                //blended visuals have to be free to move away from the body
+                _ if config.visuals_on_child() => {
+                    bone_commands.with_children(|parent| {
+                        parent.spawn((
+                            PbrBundle {
+                                mesh: meshes.add(Sphere::new(joint.radius)),
+                                material: materials.add(color),
+                                ..Default::default()
+                            },
+                            JointVisual,
+                        ));
+                    });
+                }
+                _ => {
+                    bone_commands.insert((meshes.add(Sphere::new(joint.radius)), materials.add(color)));
+                }
+            }
+
+            if physics_bone.is_tip() {
+                bone_commands.insert(TipBone);
+            }
+
+            if let Some(on_bone_spawn) = on_bone_spawn.0 {
+                on_bone_spawn(&mut bone_commands, *hand, physics_bone);
+            }
+            let bone_entity = bone_commands.id();
+            physics_hands_res.set(*hand, physics_bone, bone_entity);
+
+            if let Some(hand_root) = hand_root {
+                commands.entity(hand_root).add_child(bone_entity);
+            }
+        }
+
+        if config.compound_fingers {
+            for finger in Finger::ALL.iter() {
+                let finger_bones = finger.bones();
+                let base = *hand_joints.joint(finger_bones[0]);
+                let base_inverse = base.orientation.inverse();
+                let to_local = |position: Vec3| base_inverse * (position - base.position);
+
+                //one capsule per segment, expressed in the metacarpal's frame
+                let shapes: Vec<(Vec3, Quat, Collider)> = finger_bones
+                    .windows(2)
+                    .map(|pair| {
+                        let start = hand_joints.joint(pair[0]);
+                        let end = hand_joints.joint(pair[1]);
+                        (
+                            Vec3::ZERO,
+                            Quat::IDENTITY,
+                            Collider::capsule(
+                                to_local(start.position),
+                                to_local(end.position),
+                                config.collider_radius(start.radius),
+                            ),
+                        )
+                    })
+                    .collect();
+
+                let mut finger_commands = commands.spawn((
+                    Name::new(format!("{:?} {:?} Finger", hand, finger)),
+                    PbrBundle {
+                        mesh: meshes.add(Sphere::new(base.radius)),
+                        material: materials.add(color),
+                        transform: Transform {
+                            translation: base.position,
+                            rotation: base.orientation,
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                    Collider::compound(shapes),
+                    config.rigid_body(),
+                    Velocity::default(),
+                    CollisionGroups::new(hand_membership, hand_filter),
+                    config.solver_groups(),
+                    finger_bones[0],
+                    *hand,
+                    HandId(*hand, finger_bones[0]),
+                    BoneTarget::default(),
+                    BoneInitState::False,
+                    FingerBody(*finger),
+                ));
+
+                //the rest of the joints are just visuals riding on the finger body
+                finger_commands.with_children(|parent| {
+                    for bone in finger_bones[1..].iter() {
+                        let joint = hand_joints.joint(*bone);
+                        parent.spawn(PbrBundle {
+                            mesh: meshes.add(Sphere::new(joint.radius)),
+                            material: materials.add(color),
+                            transform: Transform {
+                                translation: to_local(joint.position),
+                                rotation: base_inverse * joint.orientation,
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        });
+                    }
+                });
+
+                if let Some(on_bone_spawn) = on_bone_spawn.0 {
+                    on_bone_spawn(&mut finger_commands, *hand, finger_bones[0]);
+                }
+                let finger_entity = finger_commands.id();
+                physics_hands_res.set(*hand, finger_bones[0], finger_entity);
+
+                if let Some(hand_root) = hand_root {
+                    commands.entity(hand_root).add_child(finger_entity);
+                }
+            }
         }
 
         // for bone in bones.iter() {
@@ -1214,11 +1452,13 @@ pub fn spawn_physics_hands(
     
     }
 
+    commands.insert_resource(physics_hands_res);
 }
 
 
 pub fn update_physics_hands(
     hands_res: Option<Res<HandsResource>>,
+    physics_hands_res: Option<Res<PhysicsHandsResource>>,
     mut bone_query: Query<(
         &mut Transform,
         &mut Collider,
@@ -1226,102 +1466,682 @@ pub fn update_physics_hands(
         &mut BoneInitState,
         &Hand,
         &mut Velocity,
-    )>,
-    hand_query: Query<(&Transform, &HandBone, &Hand), Without<PhysicsHandBone>>,
+        Option<&mut BoneTarget>,
+        Option<&mut ColliderLength>,
+    ), Without<BoneDisabled>>,
+    driven_pose: Res<DrivenPose>,
+    target_source: Option<Res<HandTargetSource>>,
+    joint_query: OxrJointQuery,
+    tracked_hands: Res<TrackedHands>,
     time: Res<Time>,
+    config: Res<HandPhysicsConfig>,
+    #[cfg(feature = "debug")] debug_config: Res<HandDebugConfig>,
 ) {
 
-    let matching = MatchingType::VelocityMatching;
-    //sanity check do we even have hands?
-    match hands_res {
-        Some(res) => {
+    //the last frames of a shutdown can step without time passing, nothing to match against then
+    let delta = time.delta_seconds();
+    if delta <= 0.0 {
+        return;
+    }
+
+    //a driven hand goes first, then a HandTargetSource replaces bevy_oxr, otherwise sample the
+    //tracked joint entities. during teardown the resources or any of the entities below can
+    //already be gone, every lookup skips rather than unwraps
+    let oxr_targets;
+    let provider: Option<&dyn HandTargetProvider> = match (target_source.as_deref(), hands_res) {
+        (Some(source), _) => Some(source.0.as_ref()),
+        (None, Some(hands_res)) => {
+            oxr_targets = OxrHandTargets::sample(&hands_res, &joint_query, &tracked_hands, &config, time.elapsed_seconds_f64());
+            Some(&oxr_targets)
+        }
+        (None, None) => {
+            #[cfg(feature = "debug")]
+            if debug_config.enabled {
+                debug!("hand states resource not initialized yet");
+            }
+            None
+        }
+    };
+
+    for hand in [Hand::Left, Hand::Right] {
+        let _hand_span = trace_span!("update_physics_hands", hand = ?hand).entered();
 
-            //config stuff
-            let radius = 0.010;
-            for mut bone in bone_query.iter_mut() {
+        //drive exactly the colliders spawn_physics_hands recorded for this hand
+        let Some(physics_hands_res) = physics_hands_res.as_ref() else {
+            continue;
+        };
+        let Some(joints) = driven_pose
+            .targets(hand)
+            .or_else(|| provider.and_then(|provider| provider.targets(hand)))
+        else {
+            continue;
+        };
+        for entity in physics_hands_res.entities(hand) {
+            let Ok(mut bone) = bone_query.get_mut(entity) else {
+                continue;
+            };
+            if *bone.4 != hand {
+                continue;
+            }
 
-                if *bone.4 == Hand::Left {
+            let result = segment_end(*bone.2);
+            if let Some(end_bone) = result {
+                //now we need their transforms
+                let to_transform = |joint: &HandJoint| Transform {
+                    translation: joint.position,
+                    rotation: joint.orientation,
+                    ..Default::default()
+                };
+                let start_transform = to_transform(joints.joint(*bone.2));
+                let end_transform = to_transform(joints.joint(end_bone));
+
+                //skip this frame for the bone rather than feed a broken rotation into the matching
+                let (Some(start_rotation), Some(_)) = (
+                    sanitize_orientation(start_transform.rotation),
+                    sanitize_orientation(end_transform.rotation),
+                ) else {
+                    continue;
+                };
+                let start_rotation = config.corrected_orientation(start_rotation);
+
+                //roll the bones with the hand rather than the world
+                let up = match config.bone_up {
+                    BoneUpAxis::World => Vec3::Y,
+                    BoneUpAxis::Palm => sanitize_orientation(joints.joint(PhysicsHandBone::Palm).orientation)
+                        .map(|rotation| config.corrected_orientation(rotation) * Vec3::Y)
+                        .unwrap_or(Vec3::Y),
+                };
+
+                let direction = end_transform.translation - start_transform.translation;
+                if direction.length() < 0.001 {
+                    //i hate this but we need to skip init if the length is zero,
+                    //only for this bone though, the others can still be driven
                     continue;
                 }
 
-                let hand_res = match bone.4 {
-                    Hand::Left => res.left,
-                    Hand::Right => res.right,
-                };
+                if let Some(bone_target) = bone.6.as_mut() {
+                    bone_target.0 = start_transform.looking_at(end_transform.translation, up);
+                }
 
-                //lets just do the Right ThumbMetacarpal for now
-                let result = get_start_and_end_entities(hand_res, bone.2);
-                if let Some((start_entity, end_entity)) = result {
-                    //now we need their transforms
-                    let start_components = hand_query.get(start_entity);
-                    let end_components = hand_query.get(end_entity);
-                    let direction = end_components.unwrap().0.translation
-                        - start_components.unwrap().0.translation;
-                    if direction.length() < 0.001 {
-                        //i hate this but we need to skip init if the length is zero
-                        return;
-                    }
+                #[cfg(feature = "debug")]
+                if debug_config.enabled {
+                    let target = start_transform.translation;
+                    trace!(
+                        bone = ?bone.2,
+                        target = ?target,
+                        actual = ?bone.0.translation,
+                        error = target.distance(bone.0.translation),
+                        "bone state"
+                    );
+                }
 
-                    match *bone.3 {
-                        BoneInitState::True => {
-                            match matching {
-                                MatchingType::PositionMatching => {
-                                    //if we are init then we just move em?
-                                    *bone.0 = start_components
-                                        .unwrap()
-                                        .0
-                                        .clone()
-                                        .looking_at(end_components.unwrap().0.translation, Vec3::Y);
-                                }
-                                MatchingType::VelocityMatching => {
-                                    //calculate position difference
-                                    let diff = (start_components.unwrap().0.translation
-                                        - bone.0.translation)
-                                        / time.delta_seconds();
-                                    bone.5.linvel = diff;
-                                    //calculate angular velocity?
-                                    // gizmos.ray(bone.0.translation, bone.0.forward(), Color::WHITE);
-                                    let desired_forward = start_components
-                                        .unwrap()
-                                        .0
-                                        .clone()
-                                        .looking_at(end_components.unwrap().0.translation, Vec3::Y)
-                                        .rotation;
-                                    // gizmos.ray(
-                                    //     bone.0.translation,
-                                    //     desired_forward.mul_vec3(-Vec3::Z),
-                                    //     Color::GREEN,
-                                    // );
-                                    let cross =
-                                        bone.0.forward().cross(desired_forward.mul_vec3(-Vec3::Z));
-
-                                    // gizmos.ray(
-                                    //     bone.0.translation,
-                                    //     cross,
-                                    //     Color::RED,
-                                    // );
-                                    bone.5.angvel = cross / time.delta_seconds();
-                                }
+                match *bone.3 {
+                    BoneInitState::True => {
+                        match config.matching_for(*bone.2) {
+                            MatchingType::PositionMatching => {
+                                //if we are init then we just move em?
+                                *bone.0 = if config.match_tracked_orientation {
+                                    //keep the tracked roll that looking_at would throw away
+                                    Transform {
+                                        rotation: start_rotation,
+                                        ..start_transform
+                                    }
+                                } else {
+                                    start_transform.looking_at(end_transform.translation, up)
+                                };
+                            }
+                            MatchingType::VelocityMatching => {
+                                //calculate position difference
+                                let diff = (start_transform.translation
+                                    - bone.0.translation)
+                                    / delta;
+                                bone.5.linvel = diff;
+                                //calculate angular velocity?
+                                // gizmos.ray(bone.0.translation, bone.0.forward(), Color::WHITE);
+                                let desired_forward = start_transform
+                                    .looking_at(end_transform.translation, up)
+                                    .rotation;
+                                // gizmos.ray(
+                                //     bone.0.translation,
+                                //     desired_forward.mul_vec3(-Vec3::Z),
+                                //     Color::GREEN,
+                                // );
+                                let cross =
+                                    bone.0.forward().cross(desired_forward.mul_vec3(-Vec3::Z));
+
+                                // gizmos.ray(
+                                //     bone.0.translation,
+                                //     cross,
+                                //     Color::RED,
+                                // );
+                                bone.5.angvel = cross / delta;
                             }
                         }
-                        BoneInitState::False => {
-                            //build a new collider?
-                            *bone.1 = Collider::capsule(
-                                Vec3::splat(0.0),
-                                Vec3 {
-                                    x: 0.0,
-                                    y: 0.0,
-                                    z: -direction.length(),
-                                },
-                                radius,
-                            );
-                            *bone.3 = BoneInitState::True;
-                        }
                     }
+                    BoneInitState::False => {
+                        *bone.3 = BoneInitState::True;
+                    }
+                }
+
+                //building a collider allocates, only rebuild once the bone length really changed
+                if let Some(collider_length) = bone.7.as_mut() {
+                    //nearly zero length capsules are degenerate for rapier
+                    let length = direction.length().max(config.min_collider_length);
+                    let needs_rebuild = match collider_length.0 {
+                        Some(cached) => (cached - length).abs() > config.collider_rebuild_threshold,
+                        None => true,
+                    };
+
+                    if needs_rebuild {
+                        //the tracked radius with live_joint_radius, otherwise the default one
+                        //spawn_physics_hands sized the bone with
+                        let joint = joints.joint(*bone.2);
+                        let radius = if config.live_joint_radius
+                            && joint.position_tracked
+                            && joint.radius > 0.0
+                        {
+                            joint.radius
+                        } else {
+                            joint_radius(hand, *bone.2)
+                        };
+                        *bone.1 = Collider::capsule(
+                            Vec3::splat(0.0),
+                            Vec3 {
+                                x: 0.0,
+                                y: 0.0,
+                                z: -length,
+                            },
+                            config.collider_radius(radius),
+                        );
+                        collider_length.0 = Some(length);
+                    }
+                }
+            }
+        }
+    }
+
+}
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::test_utils::{
+        bone_entity, drive, drive_once, hand_app, move_joint_entities, spawn_joint_entities, step,
+    };
+
+    #[test]
+    fn spawn_and_update_stay_quiet_without_debugging() {
+        use crate::debug::HandDebugConfig;
+        use crate::test_utils::capture_tracing;
+
+        let (_, lines) = capture_tracing(|| {
+            let mut app = hand_app(HandPhysicsConfig::default());
+            assert!(!app.world.resource::<HandDebugConfig>().enabled);
+            assert!(app.world.get_resource::<PhysicsHandsResource>().is_some());
+
+            //driven with no tracking resources around, every bone is updated
+            let origin = *app.world.resource::<HandOrigin>();
+            let placement = *app.world.resource::<HandPlacement>();
+            let raised = rest_pose(Hand::Right, &origin, &placement)
+                .transformed(&HandOrigin(Transform::from_xyz(0.0, 0.1, 0.0)));
+            drive(&mut app, Hand::Right, raised);
+            step(&mut app, 3);
+        });
+
+        assert!(!lines.iter().any(|line| line.contains("target=xr_hand")
+            && (line.contains("level=DEBUG") || line.contains("level=TRACE"))));
+    }
+
+    #[test]
+    fn joint_count_mismatch_is_refused() {
+        assert!(check_joint_count(HandBone::get_all_bones().len()));
+        assert!(!check_joint_count(HAND_JOINT_COUNT - 1));
+        assert!(!check_joint_count(HAND_JOINT_COUNT + 4));
+    }
+
+    #[test]
+    fn every_finger_bone_decomposes_back_to_its_finger() {
+        for index in 0..HAND_JOINT_COUNT {
+            let bone = NameToHandJoint::get_physics_bone_from_index(index);
+            match bone.decompose() {
+                Some((finger, segment)) => {
+                    assert!(finger.bones().contains(&bone));
+                    assert_eq!(segment == Segment::Tip, finger.tip() == bone);
+                }
+                None => assert!(matches!(bone, PhysicsHandBone::Palm | PhysicsHandBone::Wrist)),
+            }
+        }
+        assert_eq!(
+            decompose(PhysicsHandBone::ThumbDistal),
+            Some((Finger::Thumb, Segment::Distal))
+        );
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn bone_state_is_traced_only_while_debugging() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        use crate::test_utils::{capture_tracing, step, FixedTargets};
+
+        let mut app = hand_app(HandPhysicsConfig::default());
+        app.insert_resource(HandTargetSource::new(FixedTargets {
+            left: get_default_hand(&Hand::Left),
+            right: get_default_hand(&Hand::Right),
+        }));
+        step(&mut app, 1);
+
+        let (_, quiet) = capture_tracing(|| app.world.run_system_once(update_physics_hands));
+        assert!(!quiet.iter().any(|line| line.contains("bone state")));
+
+        app.world.resource_mut::<HandDebugConfig>().enabled = true;
+        let (_, traced) = capture_tracing(|| app.world.run_system_once(update_physics_hands));
+        assert!(traced
+            .iter()
+            .any(|line| line.contains("bone state") && line.contains("bone=IndexProximal")));
+    }
+
+    #[test]
+    fn palm_up_axis_rolls_the_bones_with_the_hand() {
+        let roll = HandOrigin(Transform::from_rotation(Quat::from_rotation_z(1.2)));
+        let rolled = get_default_hand(&Hand::Right).transformed(&roll);
+
+        let mut app = hand_app(HandPhysicsConfig {
+            bone_up: BoneUpAxis::Palm,
+            ..Default::default()
+        });
+        drive_once(&mut app, get_default_hand(&Hand::Left), rolled);
+
+        //looking_at keeps the up hint in the plane of the bone's forward and up axes
+        let target = app
+            .world
+            .get::<BoneTarget>(bone_entity(&app, Hand::Right, PhysicsHandBone::IndexProximal))
+            .unwrap()
+            .0;
+        let palm_up = rolled.joint(PhysicsHandBone::Palm).orientation * Vec3::Y;
+        assert!((target.rotation * Vec3::X).dot(palm_up).abs() < 1e-4);
+        assert!((target.rotation * Vec3::Y).dot(palm_up) > 0.0);
+    }
+
+    #[test]
+    fn rebuilt_capsules_keep_the_configured_radius() {
+        let mut app = hand_app(HandPhysicsConfig {
+            render_to_collider_ratio: 0.8,
+            ..Default::default()
+        });
+        //longer segments than the rest pose, so every capsule gets rebuilt
+        let grown = get_default_hand(&Hand::Right).scaled(1.3);
+        drive_once(&mut app, get_default_hand(&Hand::Left), grown);
+
+        let bone = bone_entity(&app, Hand::Right, PhysicsHandBone::IndexProximal);
+        let capsule_radius = app
+            .world
+            .get::<Collider>(bone)
+            .and_then(|collider| collider.as_capsule().map(|capsule| capsule.radius()))
+            .unwrap();
+        let expected = joint_radius(Hand::Right, PhysicsHandBone::IndexProximal) * 0.8;
+        assert!((capsule_radius - expected).abs() < 1e-6);
+        assert_eq!(
+            app.world.get::<ColliderLength>(bone).unwrap().0,
+            Some(
+                grown
+                    .joint(PhysicsHandBone::IndexProximal)
+                    .position
+                    .distance(grown.joint(PhysicsHandBone::IndexIntermediate).position)
+            )
+        );
+    }
+
+    #[test]
+    fn compound_fingers_spawn_one_body_per_finger() {
+        let mut app = hand_app(HandPhysicsConfig {
+            compound_fingers: true,
+            ..Default::default()
+        });
+
+        let mut fingers = app.world.query::<(&FingerBody, &Hand, &Collider)>();
+        let right: Vec<_> = fingers
+            .iter(&app.world)
+            .filter(|(_, hand, _)| **hand == Hand::Right)
+            .collect();
+        assert_eq!(right.len(), Finger::ALL.len());
+        for (finger, _, collider) in right {
+            //one capsule per segment between the finger's joints
+            let shapes = collider.as_compound().unwrap().shapes().count();
+            assert_eq!(shapes, finger.0.bones().len() - 1);
+        }
+
+        //the finger joints are folded into the bodies, only palm and wrist stay separate
+        let physics_hands = app.world.resource::<PhysicsHandsResource>();
+        assert_eq!(physics_hands.entities(Hand::Right).count(), 2 + Finger::ALL.len());
+    }
+
+    #[test]
+    fn bone_connections_form_a_tree_rooted_at_the_wrist() {
+        assert_eq!(BONE_CONNECTIONS.len(), 25);
+
+        let parent = |bone: PhysicsHandBone| {
+            let parents: Vec<_> = BONE_CONNECTIONS
+                .iter()
+                .filter(|(_, child)| *child == bone)
+                .map(|(parent, _)| *parent)
+                .collect();
+            assert!(parents.len() <= 1, "{:?} has more than one parent", bone);
+            parents.first().copied()
+        };
+
+        assert_eq!(parent(PhysicsHandBone::Wrist), None);
+        for index in 0..HAND_JOINT_COUNT {
+            //every joint walks up to the wrist without going round in circles
+            let mut bone = NameToHandJoint::get_physics_bone_from_index(index);
+            for _ in 0..HAND_JOINT_COUNT {
+                match parent(bone) {
+                    Some(next) => bone = next,
+                    None => break,
                 }
             }
+            assert_eq!(bone, PhysicsHandBone::Wrist);
         }
-        None => info!("hand states resource not initialized yet"),
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn jittering_lengths_rebuild_the_capsule_once() {
+        let mut app = hand_app(HandPhysicsConfig::default());
+        let bone = bone_entity(&app, Hand::Right, PhysicsHandBone::IndexProximal);
+        let length = |joints: &HandJoints| {
+            joints
+                .joint(PhysicsHandBone::IndexProximal)
+                .position
+                .distance(joints.joint(PhysicsHandBone::IndexIntermediate).position)
+        };
+
+        let grown = get_default_hand(&Hand::Right).scaled(1.3);
+        drive_once(&mut app, get_default_hand(&Hand::Left), grown);
+        let built = app.world.get::<ColliderLength>(bone).unwrap().0;
+        assert_eq!(built, Some(length(&grown)));
+
+        //tracking noise well under collider_rebuild_threshold
+        for frame in 0..10 {
+            let jitter = if frame % 2 == 0 { 0.001 } else { -0.001 };
+            let jittered = get_default_hand(&Hand::Right).scaled(1.3 + jitter);
+            assert!((length(&jittered) - length(&grown)).abs() > 0.0);
+            drive_once(&mut app, get_default_hand(&Hand::Left), jittered);
+            assert_eq!(app.world.get::<ColliderLength>(bone).unwrap().0, built);
+        }
+    }
+
+    #[test]
+    fn short_bones_keep_the_minimum_capsule() {
+        let config = HandPhysicsConfig::default();
+        let min_length = config.min_collider_length;
+        let mut app = hand_app(config);
+        let bone = bone_entity(&app, Hand::Right, PhysicsHandBone::IndexProximal);
+
+        for step in 0..=6 {
+            let length = 0.0005 + 0.00025 * step as f32;
+            let mut joints = get_default_hand(&Hand::Right);
+            let proximal = joints.joint(PhysicsHandBone::IndexProximal).position;
+            let direction =
+                (joints.joint(PhysicsHandBone::IndexIntermediate).position - proximal).normalize();
+            joints.inner[PhysicsHandBone::IndexIntermediate.index()].position =
+                proximal + direction * length;
+            drive_once(&mut app, get_default_hand(&Hand::Left), joints);
+
+            let segment = app.world.get::<Collider>(bone).unwrap().as_capsule().unwrap().segment();
+            assert!(segment.a().distance(segment.b()) >= min_length - 1e-6, "{}", length);
+            if let Some(built) = app.world.get::<ColliderLength>(bone).unwrap().0 {
+                assert!(built >= min_length);
+            }
+        }
+    }
+
+    #[test]
+    fn all_bone_entities_lists_every_spawned_bone() {
+        use bevy::ecs::query::With;
+
+        let mut app = hand_app(HandPhysicsConfig::default());
+        let all = app.world.resource::<PhysicsHandsResource>().all_bone_entities();
+
+        let spawned = app.world.query_filtered::<Entity, With<PhysicsHandBone>>().iter(&app.world).count();
+        assert_eq!(all.len(), spawned);
+        assert_eq!(all.len(), 2 * HAND_JOINT_COUNT);
+        for entity in all {
+            assert!(app.world.get::<PhysicsHandBone>(entity).is_some());
+        }
+    }
+
+    // the sphere meshes drawn for the physics bones, on the bones themselves or on a child
+    fn joint_meshes(instanced_joints: bool) -> Vec<bevy::asset::AssetId<Mesh>> {
+        use bevy::{asset::Handle, hierarchy::Parent};
+
+        let mut app = hand_app(HandPhysicsConfig { instanced_joints, ..Default::default() });
+        let bones = app.world.resource::<PhysicsHandsResource>().all_bone_entities();
+
+        let mut meshes = app.world.query::<(Entity, Option<&Parent>, &Handle<Mesh>)>();
+        meshes
+            .iter(&app.world)
+            .filter(|(entity, parent, _)| {
+                bones.contains(entity) || parent.is_some_and(|parent| bones.contains(&parent.get()))
+            })
+            .map(|(_, _, mesh)| mesh.id())
+            .collect()
+    }
+
+    #[test]
+    fn instanced_joints_share_one_mesh() {
+        let instanced = joint_meshes(true);
+        assert_eq!(instanced.len(), 2 * HAND_JOINT_COUNT);
+        assert!(instanced.iter().all(|mesh| *mesh == instanced[0]));
+
+        let separate = joint_meshes(false);
+        assert_eq!(separate.len(), 2 * HAND_JOINT_COUNT);
+        assert!(separate.iter().any(|mesh| *mesh != separate[0]));
+    }
+
+    #[test]
+    fn position_matching_keeps_the_tracked_roll() {
+        let mut app = hand_app(HandPhysicsConfig {
+            matching: MatchingType::PositionMatching,
+            match_tracked_orientation: true,
+            ..Default::default()
+        });
+        let bone = bone_entity(&app, Hand::Right, PhysicsHandBone::IndexProximal);
+
+        let mut rolled = get_default_hand(&Hand::Right);
+        let joint = &mut rolled.inner[PhysicsHandBone::IndexProximal.index()];
+        joint.orientation = (joint.orientation * Quat::from_rotation_z(0.5)).normalize();
+        let expected = joint.orientation;
+
+        //the first step only initializes the bones
+        for _ in 0..2 {
+            drive_once(&mut app, get_default_hand(&Hand::Left), rolled);
+        }
+
+        let rotation = app.world.get::<Transform>(bone).unwrap().rotation;
+        assert!(rotation.angle_between(expected) < 1e-4);
+        //looking down the segment would have lost the roll
+        let start = rolled.joint(PhysicsHandBone::IndexProximal).position;
+        let end = rolled.joint(PhysicsHandBone::IndexIntermediate).position;
+        let looking_at = Transform::from_translation(start).looking_at(end, Vec3::Y).rotation;
+        assert!(rotation.angle_between(looking_at) > 0.1);
+    }
+
+    #[test]
+    fn orientation_correction_turns_the_bones() {
+        let correction = Quat::from_rotation_z(std::f32::consts::FRAC_PI_2);
+        let mut app = hand_app(HandPhysicsConfig {
+            matching: MatchingType::PositionMatching,
+            match_tracked_orientation: true,
+            orientation_correction: correction,
+            ..Default::default()
+        });
+        let bone = bone_entity(&app, Hand::Right, PhysicsHandBone::IndexProximal);
+
+        let joints = get_default_hand(&Hand::Right);
+        for _ in 0..2 {
+            drive_once(&mut app, get_default_hand(&Hand::Left), joints);
+        }
+
+        let tracked = joints.joint(PhysicsHandBone::IndexProximal).orientation;
+        let rotation = app.world.get::<Transform>(bone).unwrap().rotation;
+        assert!(rotation.angle_between(tracked * correction) < 1e-4);
+        assert!((rotation.angle_between(tracked) - std::f32::consts::FRAC_PI_2).abs() < 1e-4);
+    }
+
+    #[test]
+    fn hand_ids_are_unique_across_both_hands() {
+        let mut app = hand_app(HandPhysicsConfig::default());
+
+        let mut seen = [[false; HAND_JOINT_COUNT]; 2];
+        let mut ids = app.world.query::<&HandId>();
+        for HandId(hand, bone) in ids.iter(&app.world) {
+            let slot = &mut seen[*hand as usize][bone.index()];
+            assert!(!*slot, "{:?} {:?} spawned twice", hand, bone);
+            *slot = true;
+        }
+        assert!(seen.iter().flatten().all(|seen| *seen));
+
+        //and the lookup by id finds the same entity as the resource
+        let index_tip = app
+            .world
+            .query::<(Entity, &HandId)>()
+            .iter(&app.world)
+            .find(|(_, id)| **id == HandId(Hand::Left, PhysicsHandBone::IndexTip));
+        assert_eq!(
+            index_tip.map(|(entity, _)| entity),
+            Some(bone_entity(&app, Hand::Left, PhysicsHandBone::IndexTip))
+        );
+    }
+
+    #[test]
+    fn joint_data_is_the_recorded_table() {
+        use NameToHandJoint::*;
+        let names = [
+            Palm, Wrist, ThumbMetacarpal, ThumbProximal, ThumbDistal, ThumbTip,
+            IndexMetacarpal, IndexProximal, IndexIntermediate, IndexDistal, IndexTip,
+            MiddleMetacarpal, MiddleProximal, MiddleIntermediate, MiddleDistal, MiddleTip,
+            RingMetacarpal, RingProximal, RingIntermediate, RingDistal, RingTip,
+            LittleMetacarpal, LittleProximal, LittleIntermediate, LittleDistal, LittleTip,
+        ];
+
+        for (hand, table) in [
+            (Hand::Left, get_default_left_hand()),
+            (Hand::Right, get_default_right_hand()),
+        ] {
+            for (index, name) in names.iter().enumerate() {
+                assert_eq!(name.index(), index);
+                let joint = name.get_joint_data(&hand);
+                let expected = table.inner[index];
+                assert_eq!(joint.position, expected.position, "joint {}", index);
+                assert_eq!(joint.orientation, expected.orientation, "joint {}", index);
+                assert_eq!(joint.radius, expected.radius, "joint {}", index);
+            }
+        }
+    }
+
+    #[test]
+    fn registered_bones_are_the_ones_driven() {
+        let mut app = hand_app(HandPhysicsConfig::default());
+        let registered = app.world.resource::<PhysicsHandsResource>().clone();
+        for hand in [Hand::Left, Hand::Right] {
+            for index in 0..HAND_JOINT_COUNT {
+                let bone = NameToHandJoint::get_physics_bone_from_index(index);
+                let entity = registered.get(hand, bone).unwrap();
+                assert_eq!(app.world.get::<PhysicsHandBone>(entity), Some(&bone));
+                assert_eq!(app.world.get::<Hand>(entity), Some(&hand));
+            }
+        }
+
+        let origin = *app.world.resource::<HandOrigin>();
+        let placement = *app.world.resource::<HandPlacement>();
+        let raised = rest_pose(Hand::Right, &origin, &placement)
+            .transformed(&HandOrigin(Transform::from_xyz(0.0, 0.1, 0.0)));
+        drive_once(&mut app, rest_pose(Hand::Left, &origin, &placement), raised);
+
+        let bone = registered.get(Hand::Right, PhysicsHandBone::IndexProximal).unwrap();
+        assert!(app.world.get::<Velocity>(bone).unwrap().linvel.y > 0.0);
+    }
+
+    #[test]
+    fn a_despawned_joint_entity_skips_instead_of_panicking() {
+        let mut app = hand_app(HandPhysicsConfig::default());
+        //a tracked joint entity for every joint, raised so the bones have somewhere to go
+        let origin = *app.world.resource::<HandOrigin>();
+        let placement = *app.world.resource::<HandPlacement>();
+        let raised = rest_pose(Hand::Right, &origin, &placement)
+            .transformed(&HandOrigin(Transform::from_xyz(0.0, 0.1, 0.0)));
+        spawn_joint_entities(&mut app.world, &rest_pose(Hand::Left, &origin, &placement), &raised);
+
+        let bone = bone_entity(&app, Hand::Right, PhysicsHandBone::IndexDistal);
+        *app.world.get_mut::<BoneInitState>(bone).unwrap() = BoneInitState::True;
+        let drive = |app: &mut bevy::app::App| {
+            step(app, 1);
+            app.world.run_system_once(update_physics_hands);
+            app.world.get::<Velocity>(bone).unwrap().linvel
+        };
+        assert!(drive(&mut app).length() > 0.0);
+
+        let tip = app.world.resource::<HandsResource>().right.index.tip;
+        app.world.despawn(tip);
+        *app.world.get_mut::<Velocity>(bone).unwrap() = Velocity::zero();
+        assert_eq!(drive(&mut app), Vec3::ZERO);
+    }
+
+    #[test]
+    fn a_torn_down_world_updates_as_a_no_op() {
+        let mut app = hand_app(HandPhysicsConfig::default());
+        let origin = *app.world.resource::<HandOrigin>();
+        let placement = *app.world.resource::<HandPlacement>();
+        let raised = rest_pose(Hand::Right, &origin, &placement)
+            .transformed(&HandOrigin(Transform::from_xyz(0.0, 0.1, 0.0)));
+        spawn_joint_entities(
+            &mut app.world,
+            &rest_pose(Hand::Left, &origin, &placement),
+            &rest_pose(Hand::Right, &origin, &placement),
+        );
+        step(&mut app, 2);
+        //the hand moves up, the bones haven't followed yet
+        move_joint_entities(&mut app.world, Hand::Right, &raised);
+
+        let bone = bone_entity(&app, Hand::Right, PhysicsHandBone::IndexProximal);
+        let update = |app: &mut bevy::app::App| {
+            *app.world.get_mut::<Velocity>(bone).unwrap() = Velocity::zero();
+            app.world.run_system_once(update_physics_hands);
+            app.world.get::<Velocity>(bone).unwrap().linvel
+        };
+        assert!(update(&mut app).y > 0.0);
+
+        //shutdown takes the tracking resource first, the bones are left where they are
+        app.world.remove_resource::<HandsResource>();
+        assert_eq!(update(&mut app), Vec3::ZERO);
+
+        //a driven hand doesn't need it, but some of its bones are already gone
+        drive(&mut app, Hand::Right, raised);
+        let tip = bone_entity(&app, Hand::Right, PhysicsHandBone::IndexTip);
+        app.world.despawn(tip);
+        assert!(update(&mut app).y > 0.0);
+
+        app.world.remove_resource::<PhysicsHandsResource>();
+        assert_eq!(update(&mut app), Vec3::ZERO);
+    }
+
+    #[test]
+    fn capsules_are_as_long_as_their_segment() {
+        let app = hand_app(HandPhysicsConfig::default());
+        let capsule_length = |bone| {
+            let entity = bone_entity(&app, Hand::Right, bone);
+            let segment = app.world.get::<Collider>(entity).unwrap().as_capsule().unwrap().segment();
+            segment.a().distance(segment.b())
+        };
+
+        let distal = capsule_length(PhysicsHandBone::IndexDistal);
+        let metacarpal = capsule_length(PhysicsHandBone::IndexMetacarpal);
+        assert!(distal < metacarpal);
+
+        for bone in [PhysicsHandBone::IndexDistal, PhysicsHandBone::IndexMetacarpal] {
+            let (start, end) = get_start_and_end_joints(&bone, &Hand::Right).unwrap();
+            assert!((capsule_length(bone) - start.position.distance(end.position)).abs() < 1e-5);
+        }
+    }
+}
@@ -0,0 +1,350 @@
+// everything but the config, BoneTarget and the group formatting is only built with the `debug`
+// feature, so headset builds carry none of the drawing or coloring
+#[cfg(feature = "debug")]
+use std::any::TypeId;
+
+#[cfg(feature = "debug")]
+use bevy::gizmos::config::GizmoConfigStore;
+use bevy::prelude::*;
+#[cfg(feature = "debug")]
+use bevy_oxr::xr_input::Hand;
+#[cfg(feature = "debug")]
+use bevy_rapier3d::dynamics::Velocity;
+use bevy_rapier3d::geometry::{CollisionGroups, Group};
+
+#[cfg(feature = "debug")]
+use crate::constants::PhysicsHandBone;
+
+#[derive(Resource, Debug, Clone)]
+pub struct HandDebugConfig {
+    // per bone diagnostics from the spawn and update systems, off so shipping builds stay quiet
+    pub enabled: bool,
+    // target error in metres at which the target lines turn fully red
+    pub target_error_max: f32,
+    // tint the bone materials toward `hot_color` as they move faster
+    pub velocity_colors: bool,
+    // linear speed in m/s at which a bone is drawn fully hot
+    pub velocity_color_max_speed: f32,
+    pub hot_color: Color,
+    // color each bone by its lowest collision membership bit, takes priority over the velocity tint
+    pub group_colors: bool,
+}
+
+impl Default for HandDebugConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target_error_max: 0.05,
+            velocity_colors: false,
+            velocity_color_max_speed: 2.0,
+            hot_color: Color::rgb(1.0, 0.15, 0.0),
+            group_colors: false,
+        }
+    }
+}
+
+// gizmo group all the hand debug drawing goes through, only registered when the plugin is
+// built with debugging enabled so shipping builds don't carry it
+#[cfg(feature = "debug")]
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct MyRoundGizmos {}
+
+// commanded target of a physics bone, written by update_physics_hands every step
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq)]
+pub struct BoneTarget(pub Transform);
+
+// also checks the gizmo group exists, debugging switched on at runtime without it draws nothing
+#[cfg(feature = "debug")]
+pub fn debug_enabled(
+    debug_config: Res<HandDebugConfig>,
+    gizmo_config_store: Res<GizmoConfigStore>,
+) -> bool {
+    debug_config.enabled
+        && gizmo_config_store
+            .get_config_dyn(&TypeId::of::<MyRoundGizmos>())
+            .is_some()
+}
+
+// line from each bone to its target, green when on target fading to red at `target_error_max`
+#[cfg(feature = "debug")]
+pub fn draw_bone_targets(
+    mut gizmos: Gizmos<MyRoundGizmos>,
+    debug_config: Res<HandDebugConfig>,
+    bone_query: Query<(&GlobalTransform, &BoneTarget)>,
+) {
+    for (transform, target) in bone_query.iter() {
+        let actual = transform.translation();
+        let error = actual.distance(target.0.translation);
+        let t = (error / debug_config.target_error_max).clamp(0.0, 1.0);
+
+        gizmos.line(actual, target.0.translation, Color::rgb(t, 1.0 - t, 0.0));
+    }
+}
+
+// material color a bone was spawned with, so the velocity tint has something to fade back to
+#[cfg(feature = "debug")]
+#[derive(Component, Debug, Clone, Copy)]
+pub struct BoneBaseColor(pub Color);
+
+#[cfg(feature = "debug")]
+pub fn velocity_colors_enabled(debug_config: Res<HandDebugConfig>) -> bool {
+    debug_config.velocity_colors && !debug_config.group_colors
+}
+
+#[cfg(feature = "debug")]
+pub fn group_colors_enabled(debug_config: Res<HandDebugConfig>) -> bool {
+    debug_config.group_colors
+}
+
+#[cfg(feature = "debug")]
+pub fn bone_colors_enabled(debug_config: Res<HandDebugConfig>) -> bool {
+    debug_config.velocity_colors || debug_config.group_colors
+}
+
+#[cfg(feature = "debug")]
+fn lerp_color(from: Color, to: Color, t: f32) -> Color {
+    let from = from.as_rgba_f32();
+    let to = to.as_rgba_f32();
+    Color::rgba(
+        from[0] + (to[0] - from[0]) * t,
+        from[1] + (to[1] - from[1]) * t,
+        from[2] + (to[2] - from[2]) * t,
+        from[3] + (to[3] - from[3]) * t,
+    )
+}
+
+#[cfg(feature = "debug")]
+pub fn color_bones_by_velocity(
+    mut commands: Commands,
+    debug_config: Res<HandDebugConfig>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    bone_query: Query<
+        (
+            Entity,
+            &Velocity,
+            &Handle<StandardMaterial>,
+            Option<&BoneBaseColor>,
+        ),
+        With<PhysicsHandBone>,
+    >,
+) {
+    let max_speed = debug_config.velocity_color_max_speed.max(f32::EPSILON);
+
+    for (entity, velocity, material, base_color) in bone_query.iter() {
+        let Some(material) = materials.get_mut(material) else {
+            continue;
+        };
+
+        let base_color = match base_color {
+            Some(base_color) => base_color.0,
+            None => {
+                commands
+                    .entity(entity)
+                    .insert(BoneBaseColor(material.base_color));
+                material.base_color
+            }
+        };
+
+        let t = (velocity.linvel.length() / max_speed).clamp(0.0, 1.0);
+        material.base_color = lerp_color(base_color, debug_config.hot_color, t);
+    }
+}
+
+#[cfg(feature = "debug")]
+pub fn color_bones_by_collision_group(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    bone_query: Query<
+        (
+            Entity,
+            &CollisionGroups,
+            &Handle<StandardMaterial>,
+            Option<&BoneBaseColor>,
+        ),
+        With<PhysicsHandBone>,
+    >,
+) {
+    for (entity, groups, material, base_color) in bone_query.iter() {
+        let Some(material) = materials.get_mut(material) else {
+            continue;
+        };
+
+        if base_color.is_none() {
+            commands
+                .entity(entity)
+                .insert(BoneBaseColor(material.base_color));
+        }
+
+        //spread the 32 groups around the hue wheel
+        let lowest = groups.memberships.bits().trailing_zeros().min(32);
+        material.base_color = Color::hsl(lowest as f32 * 360.0 / 32.0, 0.8, 0.5);
+    }
+}
+
+// puts the spawn colors back once the debug colors are switched off
+#[cfg(feature = "debug")]
+pub fn restore_bone_colors(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    bone_query: Query<(Entity, &Handle<StandardMaterial>, &BoneBaseColor)>,
+) {
+    for (entity, material, base_color) in bone_query.iter() {
+        if let Some(material) = materials.get_mut(material) {
+            material.base_color = base_color.0;
+        }
+        commands.entity(entity).remove::<BoneBaseColor>();
+    }
+}
+
+// ask for a dump of every bone's collision groups to the log
+#[derive(Event, Debug, Clone, Copy, Default)]
+pub struct LogCollisionGroups;
+
+// "none", "all" or the set group numbers, e.g. "1, 3, 4"
+pub fn format_groups(group: Group) -> String {
+    if group.is_empty() {
+        return "none".to_string();
+    }
+    if group.is_all() {
+        return "all".to_string();
+    }
+
+    (0..32)
+        .filter(|bit| group.bits() & (1 << bit) != 0)
+        .map(|bit| (bit + 1).to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub fn describe_collision_groups(groups: &CollisionGroups) -> String {
+    format!(
+        "memberships [{}] filters [{}]",
+        format_groups(groups.memberships),
+        format_groups(groups.filters)
+    )
+}
+
+#[cfg(feature = "debug")]
+pub fn log_collision_groups(
+    mut log_events: EventReader<LogCollisionGroups>,
+    bone_query: Query<(&PhysicsHandBone, &Hand, Option<&CollisionGroups>)>,
+) {
+    if log_events.read().last().is_none() {
+        return;
+    }
+
+    for (bone, hand, groups) in bone_query.iter() {
+        match groups {
+            Some(groups) => info!(
+                "{:?} {:?}: {}",
+                hand,
+                bone,
+                describe_collision_groups(groups)
+            ),
+            None => info!(
+                "{:?} {:?}: no collision groups, collides with everything",
+                hand, bone
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_oxr::xr_input::Hand;
+
+    use super::*;
+    use crate::config::HandPhysicsConfig;
+    use crate::constants::{get_default_hand, PhysicsHandBone};
+    use crate::pose::HandOrigin;
+    use crate::test_utils::{bone_entity, drive_once, hand_app};
+
+    #[test]
+    fn bone_targets_follow_the_driven_pose() {
+        let mut app = hand_app(HandPhysicsConfig::default());
+        let raised = get_default_hand(&Hand::Right)
+            .transformed(&HandOrigin(Transform::from_xyz(0.0, 0.1, 0.0)));
+        drive_once(&mut app, get_default_hand(&Hand::Left), raised);
+
+        let bone = bone_entity(&app, Hand::Right, PhysicsHandBone::IndexProximal);
+        let target = app.world.get::<BoneTarget>(bone).unwrap();
+        assert_eq!(
+            target.0.translation,
+            raised.joint(PhysicsHandBone::IndexProximal).position
+        );
+        //the line runs from the bone, which hasn't caught up yet
+        let actual = app.world.get::<Transform>(bone).unwrap().translation;
+        assert!(actual.distance(target.0.translation) > 0.05);
+    }
+
+    #[test]
+    fn readable_groups_match_the_spawned_bones() {
+        use bevy_rapier3d::geometry::{CollisionGroups, Group};
+
+        assert_eq!(format_groups(Group::NONE), "none");
+        assert_eq!(format_groups(Group::ALL), "all");
+        assert_eq!(format_groups(Group::GROUP_1 | Group::GROUP_4), "1, 4");
+
+        let app = hand_app(HandPhysicsConfig::default());
+        let bone = bone_entity(&app, Hand::Right, PhysicsHandBone::IndexTip);
+        let groups = app.world.get::<CollisionGroups>(bone).unwrap();
+
+        //the right hand is group 2 and ignores itself and the floor
+        let others = (4..=32).map(|group| group.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            describe_collision_groups(groups),
+            format!("memberships [2] filters [1, {}]", others.join(", "))
+        );
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn fast_bones_tint_toward_the_hot_color() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = World::new();
+        world.insert_resource(HandDebugConfig {
+            velocity_colors: true,
+            ..default()
+        });
+        world.init_resource::<Assets<StandardMaterial>>();
+
+        let bone = |world: &mut World, speed: f32| {
+            let material = world
+                .resource_mut::<Assets<StandardMaterial>>()
+                .add(StandardMaterial::from(Color::BLUE));
+            world.spawn((
+                PhysicsHandBone::IndexTip,
+                Velocity::linear(Vec3::X * speed),
+                material.clone(),
+            ));
+            material
+        };
+        let fast = bone(&mut world, 10.0);
+        let slow = bone(&mut world, 0.0);
+
+        world.run_system_once(color_bones_by_velocity);
+
+        let materials = world.resource::<Assets<StandardMaterial>>();
+        let hot = world.resource::<HandDebugConfig>().hot_color;
+        assert_eq!(materials.get(&fast).unwrap().base_color, hot);
+        assert_eq!(materials.get(&slow).unwrap().base_color, Color::BLUE);
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn gizmo_group_is_absent_while_not_debugging() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut app = hand_app(HandPhysicsConfig::default());
+        assert!(app
+            .world
+            .resource::<GizmoConfigStore>()
+            .get_config_dyn(&TypeId::of::<MyRoundGizmos>())
+            .is_none());
+
+        //switching debugging on at runtime can't draw without it
+        app.world.resource_mut::<HandDebugConfig>().enabled = true;
+        assert!(!app.world.run_system_once(debug_enabled));
+    }
+}
@@ -0,0 +1,166 @@
+use bevy::prelude::*;
+use bevy_oxr::xr_input::{
+    hands::{
+        common::{HandResource, HandsResource},
+        HandBone,
+    },
+    Hand,
+};
+
+use crate::constants::{
+    get_default_hand, get_hand_resource_entity, HandJoints, NameToHandJoint, PhysicsHandBone,
+    HAND_JOINT_COUNT,
+};
+use crate::provider::HandTargetProvider;
+
+// put alongside a `Hand` on e.g. a controller entity, the default finger pose of that hand is
+// then carried rigidly by this wrist pose instead of coming from hand tracking
+#[derive(Component, Debug, Clone, Copy)]
+pub struct WristAnchor(pub Transform);
+
+impl HandJoints {
+    // move the whole pose rigidly so the wrist joint lands on `anchor`
+    pub fn anchored_at_wrist(&self, anchor: &Transform) -> HandJoints {
+        let wrist = *self.joint(PhysicsHandBone::Wrist);
+        let rotation = anchor.rotation * wrist.orientation.inverse();
+
+        HandJoints {
+            inner: self.inner.map(|joint| {
+                let mut joint = joint;
+                joint.position = anchor.translation + rotation * (joint.position - wrist.position);
+                joint.orientation = rotation * joint.orientation;
+                joint
+            }),
+        }
+    }
+}
+
+// overwrite the tracked hand joint entities with `joints`, the physics bones then follow as usual
+pub fn write_hand_joints(
+    hand_res: &HandResource,
+    joints: &HandJoints,
+    hand_query: &mut Query<&mut Transform, (With<HandBone>, Without<PhysicsHandBone>)>,
+) {
+    for index in 0..HAND_JOINT_COUNT {
+        let bone = NameToHandJoint::get_physics_bone_from_index(index);
+        if let Ok(mut transform) = hand_query.get_mut(get_hand_resource_entity(hand_res, bone)) {
+            transform.translation = joints.inner[index].position;
+            transform.rotation = joints.inner[index].orientation;
+        }
+    }
+}
+
+pub fn follow_wrist_anchor(
+    hands_res: Option<Res<HandsResource>>,
+    anchor_query: Query<(&WristAnchor, &Hand)>,
+    mut hand_query: Query<&mut Transform, (With<HandBone>, Without<PhysicsHandBone>)>,
+) {
+    let Some(hands_res) = hands_res else {
+        return;
+    };
+
+    for (anchor, hand) in anchor_query.iter() {
+        let hand_res = match hand {
+            Hand::Left => hands_res.left,
+            Hand::Right => hands_res.right,
+        };
+
+        let joints = get_default_hand(hand).anchored_at_wrist(&anchor.0);
+        write_hand_joints(&hand_res, &joints, &mut hand_query);
+    }
+}
+
+// deterministic input for tests and non-xr demos, a set hand is what update_physics_hands drives
+// that hand towards, ahead of any HandTargetSource or the tracked joints
+#[derive(Resource, Debug, Clone, Default)]
+pub struct DrivenPose {
+    pub left: Option<HandJoints>,
+    pub right: Option<HandJoints>,
+}
+
+impl DrivenPose {
+    pub fn get(&self, hand: Hand) -> Option<&HandJoints> {
+        match hand {
+            Hand::Left => self.left.as_ref(),
+            Hand::Right => self.right.as_ref(),
+        }
+    }
+}
+
+impl HandTargetProvider for DrivenPose {
+    fn targets(&self, hand: Hand) -> Option<HandJoints> {
+        self.get(hand).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::config::HandPhysicsConfig;
+    use crate::constants::update_physics_hands;
+    use crate::pose::HandOrigin;
+    use crate::provider::HandTargetSource;
+    use crate::test_utils::{
+        bone_transform, drive, hand_app, spawn_joint_entities, step, FixedTargets,
+    };
+
+    #[test]
+    fn wrist_anchor_carries_the_default_hand() {
+        let mut world = World::new();
+        spawn_joint_entities(
+            &mut world,
+            &get_default_hand(&Hand::Left),
+            &get_default_hand(&Hand::Right),
+        );
+        let anchor = Transform::from_xyz(0.3, 1.2, -0.4).with_rotation(Quat::from_rotation_y(0.7));
+        world.spawn((WristAnchor(anchor), Hand::Right));
+
+        world.run_system_once(follow_wrist_anchor);
+
+        let hand_res = world.resource::<HandsResource>().right;
+        let wrist = world.get::<Transform>(hand_res.wrist).unwrap();
+        assert!(wrist.translation.distance(anchor.translation) < 1e-5);
+        assert!(wrist.rotation.angle_between(anchor.rotation) < 1e-4);
+
+        //rigidly, the hand keeps its shape
+        let default = get_default_hand(&Hand::Right);
+        let tip = world.get::<Transform>(hand_res.index.tip).unwrap();
+        let expected = default
+            .joint(PhysicsHandBone::IndexTip)
+            .position
+            .distance(default.joint(PhysicsHandBone::Wrist).position);
+        assert!((tip.translation.distance(wrist.translation) - expected).abs() < 1e-5);
+    }
+
+    #[test]
+    fn bones_converge_on_the_driven_pose() {
+        let mut app = hand_app(HandPhysicsConfig::default());
+        //a target source is overridden by the driven hand
+        app.insert_resource(HandTargetSource::new(FixedTargets {
+            left: get_default_hand(&Hand::Left),
+            right: get_default_hand(&Hand::Right),
+        }));
+        let raised = get_default_hand(&Hand::Right)
+            .transformed(&HandOrigin(Transform::from_xyz(0.0, 0.1, 0.0)));
+        drive(&mut app, Hand::Right, raised);
+
+        let tracked_joint = app.world.resource::<HandsResource>().right.index.proximal;
+        let tracked_before = *app.world.get::<Transform>(tracked_joint).unwrap();
+
+        for _ in 0..30 {
+            step(&mut app, 1);
+            app.world.run_system_once(update_physics_hands);
+        }
+
+        let bone = bone_transform(&app, Hand::Right, PhysicsHandBone::IndexProximal);
+        let target = raised.joint(PhysicsHandBone::IndexProximal).position;
+        assert!(bone.translation.distance(target) < 0.01);
+        //the tracking entities are left to the tracking source
+        assert_eq!(
+            *app.world.get::<Transform>(tracked_joint).unwrap(),
+            tracked_before
+        );
+    }
+}
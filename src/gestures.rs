@@ -0,0 +1,513 @@
+use std::collections::VecDeque;
+
+use bevy::{prelude::*, utils::HashSet};
+use bevy_oxr::xr_input::Hand;
+
+use crate::constants::{Finger, HandJoints, PhysicsHandBone};
+use crate::tracking::{TrackedHands, TrackedVelocities};
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum Gesture {
+    Pinch,
+    Ok,
+    Fist,
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct GestureConfig {
+    // thumb tip to index tip distance below which we call it a pinch
+    pub pinch_distance: f32,
+    // finger_straightness above which a finger counts as extended
+    pub extended_straightness: f32,
+    // finger_straightness below which a finger counts as curled into the palm
+    pub curled_straightness: f32,
+    // palm to palm distance that counts as the hands touching
+    pub clap_distance: f32,
+    // closing speed in m/s the palms need when they meet for it to be a clap
+    pub clap_min_speed: f32,
+    // palm direction reversals within `shake_window` seconds that make a shake
+    pub shake_reversals: usize,
+    pub shake_window: f32,
+    // palm speed in m/s below which a change of direction doesn't count as a reversal
+    pub shake_min_speed: f32,
+    // finger gun hammer: the thumb tip counts as down once it is within `gun_thumb_down_distance`
+    // of the index proximal joint, and up again past `gun_thumb_up_distance`. the gap between the
+    // two keeps a trembling thumb from firing twice
+    pub gun_thumb_down_distance: f32,
+    pub gun_thumb_up_distance: f32,
+}
+
+// palms came together quickly, `impact_speed` is their closing speed in m/s
+#[derive(Event, Debug, Clone, Copy)]
+pub struct HandsClap {
+    pub impact_speed: f32,
+}
+
+// palm went back and forth quickly, `intensity` is the reversal rate in reversals per second
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ShakeGesture {
+    pub hand: Hand,
+    pub intensity: f32,
+}
+
+// thumb came down while the hand held the finger gun pose
+#[derive(Event, Debug, Clone, Copy)]
+pub struct FingerGunFire {
+    pub hand: Hand,
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        Self {
+            pinch_distance: 0.02,
+            extended_straightness: 0.9,
+            curled_straightness: 0.6,
+            clap_distance: 0.05,
+            clap_min_speed: 0.5,
+            shake_reversals: 4,
+            shake_window: 1.0,
+            shake_min_speed: 0.3,
+            gun_thumb_down_distance: 0.03,
+            gun_thumb_up_distance: 0.05,
+        }
+    }
+}
+
+pub fn is_pinching(joints: &HandJoints, config: &GestureConfig) -> bool {
+    let thumb_tip = joints.joint(PhysicsHandBone::ThumbTip).position;
+    let index_tip = joints.joint(PhysicsHandBone::IndexTip).position;
+    thumb_tip.distance(index_tip) < config.pinch_distance
+}
+
+pub fn is_extended(joints: &HandJoints, finger: Finger, config: &GestureConfig) -> bool {
+    joints.finger_straightness(finger) >= config.extended_straightness
+}
+
+pub fn is_curled(joints: &HandJoints, finger: Finger, config: &GestureConfig) -> bool {
+    joints.finger_straightness(finger) < config.curled_straightness
+}
+
+// index out, middle, ring and little curled, whatever the thumb is doing
+pub fn is_finger_gun(joints: &HandJoints, config: &GestureConfig) -> bool {
+    is_extended(joints, Finger::Index, config)
+        && [Finger::Middle, Finger::Ring, Finger::Little]
+            .iter()
+            .all(|finger| is_curled(joints, *finger, config))
+}
+
+pub fn detect_gestures(joints: &HandJoints, config: &GestureConfig) -> Vec<Gesture> {
+    let mut gestures = Vec::new();
+
+    if is_pinching(joints, config) {
+        gestures.push(Gesture::Pinch);
+
+        //ok sign is a pinch with the other three fingers held out
+        if [Finger::Middle, Finger::Ring, Finger::Little]
+            .iter()
+            .all(|finger| is_extended(joints, *finger, config))
+        {
+            gestures.push(Gesture::Ok);
+        }
+    }
+
+    //the thumb can rest anywhere in a fist, only the other four have to be curled
+    if [Finger::Index, Finger::Middle, Finger::Ring, Finger::Little]
+        .iter()
+        .all(|finger| is_curled(joints, *finger, config))
+    {
+        gestures.push(Gesture::Fist);
+    }
+
+    gestures
+}
+
+// gestures each hand is holding right now, refreshed every frame from the tracked pose. the
+// physics bones lag behind it and the tips are never driven at all, so they can't tell a pinch
+#[derive(Resource, Debug, Clone, Default)]
+pub struct CurrentGestures {
+    pub left: HashSet<Gesture>,
+    pub right: HashSet<Gesture>,
+}
+
+impl CurrentGestures {
+    pub fn get(&self, hand: Hand) -> &HashSet<Gesture> {
+        match hand {
+            Hand::Left => &self.left,
+            Hand::Right => &self.right,
+        }
+    }
+
+    pub fn is_active(&self, hand: Hand, gesture: Gesture) -> bool {
+        self.get(hand).contains(&gesture)
+    }
+}
+
+pub fn update_current_gestures(
+    config: Res<GestureConfig>,
+    tracked_hands: Res<TrackedHands>,
+    mut current: ResMut<CurrentGestures>,
+) {
+    for hand in [Hand::Left, Hand::Right] {
+        let gestures: HashSet<Gesture> = tracked_hands
+            .get(hand)
+            .map(|joints| detect_gestures(joints, &config).into_iter().collect())
+            .unwrap_or_default();
+
+        //only write on change so is_changed() means a gesture started or stopped
+        if *current.get(hand) != gestures {
+            match hand {
+                Hand::Left => current.left = gestures,
+                Hand::Right => current.right = gestures,
+            }
+        }
+    }
+}
+
+pub fn detect_hands_clap(
+    config: Res<GestureConfig>,
+    tracked_hands: Res<TrackedHands>,
+    velocities: Res<TrackedVelocities>,
+    mut touching: Local<bool>,
+    mut clap_events: EventWriter<HandsClap>,
+) {
+    //the tracked palms, the physics ones are stopped short by each other's colliders
+    let palm = |hand: Hand| {
+        let palm = tracked_hands.get(hand)?.joint(PhysicsHandBone::Palm);
+        palm.position_tracked.then_some((
+            palm.position,
+            velocities.joint(hand, PhysicsHandBone::Palm)?,
+        ))
+    };
+
+    let (Some((left_position, left_velocity)), Some((right_position, right_velocity))) =
+        (palm(Hand::Left), palm(Hand::Right))
+    else {
+        *touching = false;
+        return;
+    };
+
+    let was_touching = *touching;
+    *touching = left_position.distance(right_position) < config.clap_distance;
+
+    //only the frame the palms meet, holding them together is not another clap
+    if !*touching || was_touching {
+        return;
+    }
+
+    let toward_right = (right_position - left_position).normalize_or_zero();
+    let impact_speed = (left_velocity - right_velocity).dot(toward_right);
+
+    if impact_speed >= config.clap_min_speed {
+        clap_events.send(HandsClap { impact_speed });
+    }
+}
+
+// recent palm reversals of one hand for detect_shakes
+#[derive(Default)]
+pub struct ShakeTracker {
+    direction: Option<Vec3>,
+    reversals: VecDeque<f64>,
+}
+
+pub fn detect_shakes(
+    config: Res<GestureConfig>,
+    time: Res<Time>,
+    tracked_hands: Res<TrackedHands>,
+    velocities: Res<TrackedVelocities>,
+    mut trackers: Local<[ShakeTracker; 2]>,
+    mut shake_events: EventWriter<ShakeGesture>,
+) {
+    let now = time.elapsed_seconds_f64();
+    let window = config.shake_window as f64;
+
+    for (index, hand) in [Hand::Left, Hand::Right].into_iter().enumerate() {
+        let tracker = &mut trackers[index];
+
+        //what the tracked palm did, the physics palm is held back by whatever it hits.
+        //a lost palm reads as standing still
+        let velocity = tracked_hands
+            .get(hand)
+            .filter(|joints| joints.joint(PhysicsHandBone::Palm).position_tracked)
+            .and_then(|_| velocities.joint(hand, PhysicsHandBone::Palm))
+            .unwrap_or(Vec3::ZERO);
+
+        //too slow to tell which way it's going, keep the last direction
+        if velocity.length() >= config.shake_min_speed {
+            let direction = velocity.normalize();
+            if tracker
+                .direction
+                .is_some_and(|previous| previous.dot(direction) < 0.0)
+            {
+                tracker.reversals.push_back(now);
+            }
+            tracker.direction = Some(direction);
+        }
+
+        while tracker
+            .reversals
+            .front()
+            .is_some_and(|time| now - time > window)
+        {
+            tracker.reversals.pop_front();
+        }
+
+        if tracker.reversals.len() >= config.shake_reversals.max(1) {
+            //rate over the span the reversals actually took, so shaking faster reads as stronger
+            let first = tracker.reversals.front().copied().unwrap_or(now);
+            let span = (now - first)
+                .max(time.delta_seconds_f64())
+                .max(f64::EPSILON);
+            let intensity = ((tracker.reversals.len() - 1).max(1) as f64 / span) as f32;
+
+            shake_events.send(ShakeGesture { hand, intensity });
+            //start over, one shake is one event however long it keeps going
+            tracker.reversals.clear();
+        }
+    }
+}
+
+pub fn detect_finger_gun_fire(
+    config: Res<GestureConfig>,
+    tracked_hands: Res<TrackedHands>,
+    mut thumb_down: Local<[bool; 2]>,
+    mut fire_events: EventWriter<FingerGunFire>,
+) {
+    for (index, hand) in [Hand::Left, Hand::Right].into_iter().enumerate() {
+        let Some(pose) = tracked_hands.get(hand) else {
+            thumb_down[index] = false;
+            continue;
+        };
+        let thumb_distance = pose
+            .joint(PhysicsHandBone::ThumbTip)
+            .position
+            .distance(pose.joint(PhysicsHandBone::IndexProximal).position);
+
+        let was_down = thumb_down[index];
+        thumb_down[index] = if was_down {
+            thumb_distance < config.gun_thumb_up_distance
+        } else {
+            thumb_distance < config.gun_thumb_down_distance
+        };
+
+        //only on the way down, a thumb held down doesn't keep firing
+        if thumb_down[index] && !was_down && is_finger_gun(pose, &config) {
+            fire_events.send(FingerGunFire { hand });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::constants::get_default_hand;
+    use crate::pose::HandOrigin;
+    use crate::tracking::update_tracked_velocities;
+
+    // flat hand with the thumb and index tips touching, the other three fingers held out
+    fn ok_sign(hand: Hand) -> HandJoints {
+        let mut joints = HandJoints::flat(hand);
+        let meet = joints
+            .joint(PhysicsHandBone::ThumbTip)
+            .position
+            .lerp(joints.joint(PhysicsHandBone::IndexTip).position, 0.5);
+        joints.inner[PhysicsHandBone::ThumbTip.index()].position = meet;
+        joints.inner[PhysicsHandBone::IndexTip.index()].position = meet;
+        joints
+    }
+
+    #[test]
+    fn ok_sign_is_a_pinch_with_the_other_fingers_out() {
+        let config = GestureConfig::default();
+
+        let gestures = detect_gestures(&ok_sign(Hand::Right), &config);
+        assert!(gestures.contains(&Gesture::Pinch));
+        assert!(gestures.contains(&Gesture::Ok));
+
+        let fist = detect_gestures(&HandJoints::fist(Hand::Right), &config);
+        assert!(!fist.contains(&Gesture::Ok));
+    }
+
+    #[test]
+    fn current_gestures_follow_the_tracked_pose() {
+        let mut world = World::new();
+        world.init_resource::<GestureConfig>();
+        world.init_resource::<CurrentGestures>();
+        world.insert_resource(TrackedHands {
+            left: Some(HandJoints::flat(Hand::Left)),
+            right: Some(ok_sign(Hand::Right)),
+        });
+
+        world.run_system_once(update_current_gestures);
+
+        let current = world.resource::<CurrentGestures>();
+        assert!(current.is_active(Hand::Right, Gesture::Ok));
+        assert!(current.get(Hand::Left).is_empty());
+    }
+
+    #[test]
+    fn a_held_fist_stays_current() {
+        let mut world = World::new();
+        world.init_resource::<GestureConfig>();
+        world.init_resource::<CurrentGestures>();
+        world.insert_resource(TrackedHands {
+            left: Some(HandJoints::flat(Hand::Left)),
+            right: Some(HandJoints::fist(Hand::Right)),
+        });
+
+        for _ in 0..3 {
+            world.run_system_once(update_current_gestures);
+            let current = world.resource::<CurrentGestures>();
+            assert!(current.is_active(Hand::Right, Gesture::Fist));
+            assert!(!current.is_active(Hand::Left, Gesture::Fist));
+        }
+
+        world.resource_mut::<TrackedHands>().right = Some(HandJoints::flat(Hand::Right));
+        world.run_system_once(update_current_gestures);
+        assert!(!world
+            .resource::<CurrentGestures>()
+            .is_active(Hand::Right, Gesture::Fist));
+    }
+
+    // default pose of `hand` moved so its palm sits on `position`
+    fn palm_at(hand: Hand, position: Vec3) -> HandJoints {
+        let pose = get_default_hand(&hand);
+        let offset = position - pose.joint(PhysicsHandBone::Palm).position;
+        pose.transformed(&HandOrigin(Transform::from_translation(offset)))
+    }
+
+    // tracked hands, the velocities taken from them and `systems` run after, one frame per call
+    // to `frame`
+    fn tracked_world<M>(systems: impl IntoSystemConfigs<M>) -> (World, Schedule) {
+        let mut world = World::new();
+        world.init_resource::<GestureConfig>();
+        world.init_resource::<TrackedHands>();
+        world.init_resource::<TrackedVelocities>();
+        world.insert_resource(Time::<()>::default());
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems((update_tracked_velocities, systems).chain());
+        (world, schedule)
+    }
+
+    fn frame(world: &mut World, schedule: &mut Schedule, left: Vec3, right: Vec3, delta: f32) {
+        world.insert_resource(TrackedHands {
+            left: Some(palm_at(Hand::Left, left)),
+            right: Some(palm_at(Hand::Right, right)),
+        });
+        world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs_f32(delta));
+        schedule.run(world);
+    }
+
+    #[test]
+    fn clap_speed_comes_from_the_tracked_palms() {
+        let (mut world, mut schedule) = tracked_world(detect_hands_clap);
+        world.init_resource::<Events<HandsClap>>();
+
+        //each palm closes in at 1 m/s
+        let center = Vec3::new(0.0, 1.2, -0.3);
+        for frame_index in 0..10 {
+            let half_gap = 0.1 - frame_index as f32 * 0.01;
+            frame(
+                &mut world,
+                &mut schedule,
+                center - Vec3::X * half_gap,
+                center + Vec3::X * half_gap,
+                0.01,
+            );
+        }
+
+        let claps: Vec<_> = world.resource_mut::<Events<HandsClap>>().drain().collect();
+        assert_eq!(claps.len(), 1);
+        assert!((claps[0].impact_speed - 2.0).abs() < 1e-2);
+    }
+
+    // the shakes of a right palm swinging 5cm either side at `frequency` for a second
+    fn shakes(frequency: f32) -> Vec<ShakeGesture> {
+        let (mut world, mut schedule) = tracked_world(detect_shakes);
+        world.init_resource::<Events<ShakeGesture>>();
+
+        let center = Vec3::new(0.0, 1.2, -0.3);
+        for frame_index in 0..100 {
+            let t = frame_index as f32 * 0.01;
+            let swing = 0.05 * (std::f32::consts::TAU * frequency * t).sin();
+            frame(
+                &mut world,
+                &mut schedule,
+                center - Vec3::X * 0.2,
+                center + Vec3::X * swing,
+                0.01,
+            );
+        }
+
+        let events = world.resource::<Events<ShakeGesture>>();
+        events.get_reader().read(events).copied().collect()
+    }
+
+    #[test]
+    fn faster_shaking_is_more_intense() {
+        let slow = shakes(3.0);
+        let fast = shakes(5.0);
+        assert!(!slow.is_empty());
+        assert!(slow
+            .iter()
+            .chain(&fast)
+            .all(|shake| shake.hand == Hand::Right));
+        assert!(fast[0].intensity > slow[0].intensity * 1.4);
+    }
+
+    // fist with the index held out, the thumb tip `thumb_height` above the index knuckle
+    fn finger_gun(hand: Hand, thumb_height: f32) -> HandJoints {
+        let mut joints = HandJoints::fist(hand);
+        let flat = HandJoints::flat(hand);
+        for bone in Finger::Index.bones().iter() {
+            joints.inner[bone.index()] = *flat.joint(*bone);
+        }
+        let knuckle = *joints.joint(PhysicsHandBone::IndexProximal);
+        joints.inner[PhysicsHandBone::ThumbTip.index()].position =
+            knuckle.position + knuckle.orientation * Vec3::Y * thumb_height;
+        joints
+    }
+
+    #[test]
+    fn every_thumb_pull_fires_once() {
+        let mut world = World::new();
+        world.init_resource::<GestureConfig>();
+        world.init_resource::<TrackedHands>();
+        world.init_resource::<Events<FingerGunFire>>();
+        let mut schedule = Schedule::default();
+        schedule.add_systems(detect_finger_gun_fire);
+
+        let mut fired = Vec::new();
+        let mut pull = |world: &mut World, pose: fn(Hand, f32) -> HandJoints| {
+            //down past the trigger, wobbling in the gap between the two thresholds, back up
+            for height in [
+                0.08, 0.06, 0.04, 0.025, 0.015, 0.035, 0.025, 0.045, 0.06, 0.08,
+            ] {
+                world.resource_mut::<TrackedHands>().right = Some(pose(Hand::Right, height));
+                schedule.run(world);
+                fired.extend(world.resource_mut::<Events<FingerGunFire>>().drain());
+            }
+        };
+
+        pull(&mut world, finger_gun);
+        pull(&mut world, finger_gun);
+        //the same thumb with the index curled in isn't a trigger
+        pull(&mut world, |hand, thumb_height| {
+            let mut joints = finger_gun(hand, thumb_height);
+            let fist = HandJoints::fist(hand);
+            for bone in Finger::Index.bones().iter() {
+                joints.inner[bone.index()] = *fist.joint(*bone);
+            }
+            joints
+        });
+
+        assert_eq!(fired.len(), 2);
+        assert!(fired.iter().all(|fire| fire.hand == Hand::Right));
+    }
+}
@@ -0,0 +1,797 @@
+use std::collections::VecDeque;
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_oxr::xr_input::Hand;
+use bevy_rapier3d::prelude::*;
+
+use crate::colliders::WristPalmJoint;
+use crate::constants::{hand_membership, HandJoints, PhysicsHandBone};
+use crate::gestures::{is_pinching, GestureConfig};
+use crate::queries::PhysicsHands;
+use crate::tracking::TrackedHands;
+
+// marker for dynamic bodies the hands are allowed to pick up
+#[derive(Component)]
+pub struct Grabbable;
+
+// things the hands touch without grabbing them, keeps the colliders awake like a Grabbable does
+#[derive(Component)]
+pub struct Interactable;
+
+// on a grabbed object while a hand is holding it
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Held {
+    pub hand: Hand,
+    pub anchor_entity: Entity,
+    // what to put back on release, None when the object had no CollisionGroups of its own
+    pub original_groups: Option<CollisionGroups>,
+    // object position in the anchor bone's frame at the moment it was grabbed
+    pub local_offset: Vec3,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum GrabAnchor {
+    // big objects, jointed to the palm
+    Palm,
+    // small objects, jointed between the thumb and index tips
+    PinchMidpoint,
+}
+
+impl GrabAnchor {
+    // the bone that the grab joint hangs off
+    pub fn bone(&self) -> PhysicsHandBone {
+        match self {
+            GrabAnchor::Palm => PhysicsHandBone::Palm,
+            GrabAnchor::PinchMidpoint => PhysicsHandBone::ThumbTip,
+        }
+    }
+
+    pub fn point(&self, pose: &HandJoints) -> Vec3 {
+        match self {
+            GrabAnchor::Palm => pose.joint(PhysicsHandBone::Palm).position,
+            GrabAnchor::PinchMidpoint => {
+                (pose.joint(PhysicsHandBone::ThumbTip).position
+                    + pose.joint(PhysicsHandBone::IndexTip).position)
+                    / 2.0
+            }
+        }
+    }
+}
+
+// fixed joint holding `target`'s origin at `local_offset` in the bone's frame, e.g. a tool handle
+// a little ahead of a fingertip. insert the result on `target`
+pub fn anchor_joint(bone_entity: Entity, local_offset: Vec3, target: Entity) -> ImpulseJoint {
+    debug_assert_ne!(bone_entity, target, "a bone can't be jointed to itself");
+
+    let joint = FixedJointBuilder::new()
+        .local_anchor1(local_offset)
+        .local_anchor2(Vec3::ZERO);
+    ImpulseJoint::new(bone_entity, joint)
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct GrabConfig {
+    pub anchor: GrabAnchor,
+    // how far from the anchor point a grabbable can be picked up from
+    pub grab_radius: f32,
+    // only colliders whose memberships overlap this can be grabbed, on top of the Grabbable marker
+    pub grab_layer: Group,
+    // stop held objects colliding with the hand holding them, they jitter against the fingers otherwise
+    pub ignore_holding_hand: bool,
+    // light objects buzz between the fingers even with the joint, a weak spring (1/s) toward
+    // where the joint holds them plus damping toward the hand's velocity settles them, 0 disables
+    pub hold_stiffness: f32,
+    pub hold_damping: f32,
+    // while pinching, bones that stay in contact with a grabbable for `sticky_contact_frames`
+    // frames get fixed to it so it can't slide out, released when the pinch opens
+    pub sticky: bool,
+    pub sticky_contact_frames: u32,
+    // seconds of anchor motion the release velocity is fitted over. kept much shorter than any
+    // target smoothing so a flick isn't averaged away, 0 leaves the object with whatever velocity
+    // the joint left it with
+    pub throw_window: f32,
+}
+
+impl Default for GrabConfig {
+    fn default() -> Self {
+        Self {
+            anchor: GrabAnchor::Palm,
+            grab_radius: 0.08,
+            grab_layer: Group::ALL,
+            ignore_holding_hand: true,
+            hold_stiffness: 20.0,
+            hold_damping: 0.2,
+            sticky: false,
+            sticky_contact_frames: 3,
+            throw_window: 0.05,
+        }
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct GrabState {
+    pub left: Option<Entity>,
+    pub right: Option<Entity>,
+}
+
+impl GrabState {
+    pub fn held_mut(&mut self, hand: Hand) -> &mut Option<Entity> {
+        match hand {
+            Hand::Left => &mut self.left,
+            Hand::Right => &mut self.right,
+        }
+    }
+}
+
+// recent anchor positions per hand, only used to estimate the velocity an object is thrown with
+#[derive(Resource, Debug, Default)]
+pub struct ThrowEstimator {
+    pub left: VecDeque<(f64, Vec3)>,
+    pub right: VecDeque<(f64, Vec3)>,
+}
+
+impl ThrowEstimator {
+    pub fn samples(&self, hand: Hand) -> &VecDeque<(f64, Vec3)> {
+        match hand {
+            Hand::Left => &self.left,
+            Hand::Right => &self.right,
+        }
+    }
+
+    pub fn samples_mut(&mut self, hand: Hand) -> &mut VecDeque<(f64, Vec3)> {
+        match hand {
+            Hand::Left => &mut self.left,
+            Hand::Right => &mut self.right,
+        }
+    }
+
+    // record the anchor of `hand` at `now`, dropping samples more than `window` seconds old
+    pub fn push(&mut self, hand: Hand, now: f64, point: Vec3, window: f32) {
+        let samples = self.samples_mut(hand);
+        samples.push_back((now, point));
+
+        while samples
+            .front()
+            .is_some_and(|(time, _)| now - time > window as f64)
+        {
+            samples.pop_front();
+        }
+    }
+
+    // least squares slope of position over time across the window, None with fewer than two
+    // samples or no time between them
+    pub fn velocity(&self, hand: Hand) -> Option<Vec3> {
+        let samples = self.samples(hand);
+        if samples.len() < 2 {
+            return None;
+        }
+
+        let count = samples.len() as f64;
+        let mean_time = samples.iter().map(|(time, _)| *time).sum::<f64>() / count;
+        let mean_position =
+            samples.iter().map(|(_, position)| *position).sum::<Vec3>() / count as f32;
+
+        let mut covariance = Vec3::ZERO;
+        let mut variance = 0.0;
+        for (time, position) in samples.iter() {
+            let dt = (*time - mean_time) as f32;
+            covariance += (*position - mean_position) * dt;
+            variance += dt * dt;
+        }
+
+        (variance > f32::EPSILON).then(|| covariance / variance)
+    }
+}
+
+pub fn sample_throw_velocity(
+    config: Res<GrabConfig>,
+    time: Res<Time>,
+    hands: PhysicsHands,
+    mut estimator: ResMut<ThrowEstimator>,
+) {
+    let now = time.elapsed_seconds_f64();
+
+    for hand in [Hand::Left, Hand::Right] {
+        let point = config.anchor.point(&hands.pose(hand));
+        estimator.push(hand, now, point, config.throw_window);
+    }
+}
+
+pub fn grab_objects(
+    mut commands: Commands,
+    config: Res<GrabConfig>,
+    estimator: Res<ThrowEstimator>,
+    gesture_config: Res<GestureConfig>,
+    tracked_hands: Res<TrackedHands>,
+    hands: PhysicsHands,
+    rapier_context: Res<RapierContext>,
+    mut state: ResMut<GrabState>,
+    grabbable_query: Query<
+        (Entity, &GlobalTransform, Option<&CollisionGroups>),
+        (With<Grabbable>, Without<Held>),
+    >,
+    held_query: Query<(&Held, Option<&Velocity>)>,
+) {
+    for hand in [Hand::Left, Hand::Right] {
+        let pose = tracked_hands.get(hand);
+        let pinching = pose.is_some_and(|pose| is_pinching(pose, &gesture_config));
+        let held = state.held_mut(hand);
+
+        match (*held, pinching) {
+            (Some(object), false) => {
+                //let go
+                if let Some(mut object_commands) = commands.get_entity(object) {
+                    object_commands.remove::<(ImpulseJoint, Held)>();
+
+                    if config.ignore_holding_hand {
+                        match held_query
+                            .get(object)
+                            .ok()
+                            .and_then(|(held, _)| held.original_groups)
+                        {
+                            Some(original_groups) => {
+                                object_commands.insert(original_groups);
+                            }
+                            None => {
+                                object_commands.remove::<CollisionGroups>();
+                            }
+                        }
+                    }
+
+                    if config.throw_window > 0.0 {
+                        if let Some(linvel) = estimator.velocity(hand) {
+                            let angvel = held_query
+                                .get(object)
+                                .ok()
+                                .and_then(|(_, velocity)| velocity)
+                                .map_or(Vec3::ZERO, |velocity| velocity.angvel);
+                            object_commands.insert(Velocity { linvel, angvel });
+                        }
+                    }
+                }
+                *held = None;
+            }
+            (None, true) => {
+                let (Some(pose), Some((anchor_entity, anchor_transform))) =
+                    (pose, hands.bone_entity(hand, config.anchor.bone()))
+                else {
+                    continue;
+                };
+                let anchor_point = config.anchor.point(pose);
+
+                //candidates are whatever overlaps the grab sphere on the grab layer
+                let mut candidates = Vec::new();
+                rapier_context.intersections_with_shape(
+                    anchor_point,
+                    Quat::IDENTITY,
+                    &Collider::ball(config.grab_radius),
+                    QueryFilter::new().groups(CollisionGroups::new(Group::ALL, config.grab_layer)),
+                    |entity| {
+                        candidates.push(entity);
+                        true
+                    },
+                );
+
+                let nearest = candidates
+                    .into_iter()
+                    .filter_map(|entity| grabbable_query.get(entity).ok())
+                    .map(|(entity, transform, groups)| {
+                        (
+                            entity,
+                            transform,
+                            groups.copied(),
+                            transform.translation().distance(anchor_point),
+                        )
+                    })
+                    .min_by(|a, b| a.3.total_cmp(&b.3));
+
+                let Some((object, object_transform, original_groups, _)) = nearest else {
+                    continue;
+                };
+
+                //joint the object where it currently is relative to the anchor bone
+                let object_transform = object_transform.compute_transform();
+                let joint = FixedJointBuilder::new()
+                    .local_anchor1(
+                        anchor_transform.rotation.inverse()
+                            * (anchor_point - anchor_transform.translation),
+                    )
+                    .local_basis1(anchor_transform.rotation.inverse() * object_transform.rotation)
+                    .local_anchor2(
+                        object_transform.rotation.inverse()
+                            * (anchor_point - object_transform.translation),
+                    );
+
+                commands.entity(object).insert((
+                    ImpulseJoint::new(anchor_entity, joint),
+                    Held {
+                        hand,
+                        anchor_entity,
+                        original_groups,
+                        local_offset: anchor_transform.rotation.inverse()
+                            * (object_transform.translation - anchor_transform.translation),
+                    },
+                ));
+
+                if config.ignore_holding_hand {
+                    let mut groups = original_groups.unwrap_or_default();
+                    groups.filters.remove(hand_membership(hand));
+                    commands.entity(object).insert(groups);
+                }
+                *held = Some(object);
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn stabilize_held_objects(
+    config: Res<GrabConfig>,
+    time: Res<Time>,
+    mut held_query: Query<(&Held, &GlobalTransform, &mut Velocity)>,
+    anchor_query: Query<(&GlobalTransform, &Velocity), Without<Held>>,
+) {
+    if config.hold_stiffness <= 0.0 && config.hold_damping <= 0.0 {
+        return;
+    }
+
+    for (held, transform, mut velocity) in held_query.iter_mut() {
+        let Ok((anchor_transform, anchor_velocity)) = anchor_query.get(held.anchor_entity) else {
+            continue;
+        };
+
+        let target = anchor_transform.transform_point(held.local_offset);
+        let error = target - transform.translation();
+
+        velocity.linvel += error * config.hold_stiffness * time.delta_seconds();
+        velocity.linvel = velocity
+            .linvel
+            .lerp(anchor_velocity.linvel, config.hold_damping.clamp(0.0, 1.0));
+    }
+}
+
+// on a bone currently stuck to a grabbable by the sticky grab
+#[derive(Component, Debug, Clone, Copy)]
+pub struct StuckTo(pub Entity);
+
+pub fn stick_contacting_bones(
+    mut commands: Commands,
+    config: Res<GrabConfig>,
+    gesture_config: Res<GestureConfig>,
+    tracked_hands: Res<TrackedHands>,
+    hands: PhysicsHands,
+    object_query: Query<&GlobalTransform, With<Grabbable>>,
+    stuck_query: Query<(Entity, &Hand), With<StuckTo>>,
+    mut contact_frames: Local<HashMap<(Entity, Entity), u32>>,
+) {
+    for hand in [Hand::Left, Hand::Right] {
+        let pinching = config.sticky
+            && tracked_hands
+                .get(hand)
+                .is_some_and(|pose| is_pinching(pose, &gesture_config));
+
+        if !pinching {
+            //a palm goes back on its wrist joint from joint_palms_to_wrists
+            for (bone_entity, bone_hand) in stuck_query.iter() {
+                if *bone_hand == hand {
+                    commands
+                        .entity(bone_entity)
+                        .remove::<(ImpulseJoint, StuckTo)>();
+                }
+            }
+            contact_frames.retain(|(bone_entity, _), _| {
+                hands
+                    .identify_bone(*bone_entity)
+                    .map_or(false, |(bone_hand, _)| bone_hand != hand)
+            });
+            continue;
+        }
+
+        let touching: Vec<_> = hands
+            .contacts(hand)
+            .into_iter()
+            .filter_map(|(bone, object)| {
+                let (bone_entity, bone_transform) = hands.bone_entity(hand, bone)?;
+                Some((bone_entity, object, bone_transform))
+            })
+            .collect();
+        //the frames have to be in a row, a pair that stopped touching starts over
+        contact_frames.retain(|(bone_entity, object), _| {
+            touching.iter().any(|(touching_bone, touching_object, _)| {
+                touching_bone == bone_entity && touching_object == object
+            }) || hands
+                .identify_bone(*bone_entity)
+                .map_or(false, |(bone_hand, _)| bone_hand != hand)
+        });
+
+        for (bone_entity, object, bone_transform) in touching {
+            let Ok(object_transform) = object_query.get(object) else {
+                continue;
+            };
+            if stuck_query.contains(bone_entity) {
+                continue;
+            }
+
+            let frames = contact_frames.entry((bone_entity, object)).or_insert(0);
+            *frames += 1;
+            if *frames < config.sticky_contact_frames {
+                continue;
+            }
+
+            //fix the bone to the object right where they touch, the bone is the joint's child
+            let object_transform = object_transform.compute_transform();
+            let object_inverse = object_transform.rotation.inverse();
+            let joint = FixedJointBuilder::new()
+                .local_anchor1(
+                    object_inverse * (bone_transform.translation - object_transform.translation),
+                )
+                .local_basis1(object_inverse * bone_transform.rotation)
+                .local_anchor2(Vec3::ZERO);
+
+            //a bone holds one ImpulseJoint, on the palm this takes the place of its wrist joint
+            commands
+                .entity(bone_entity)
+                .remove::<WristPalmJoint>()
+                .insert((ImpulseJoint::new(object, joint), StuckTo(object)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::config::HandPhysicsConfig;
+    use crate::pose::{rest_pose, HandOrigin, HandPlacement};
+    use crate::test_utils::{bone_entity, bone_transform, drive, hand_app, spawn_ball, step};
+
+    // a hand app with `config` and a grabbable ball sitting on `point`
+    fn grab_app(config: GrabConfig, point: Vec3) -> (App, Entity) {
+        let mut app = hand_app(HandPhysicsConfig::default());
+        app.insert_resource(config);
+        let object = spawn_ball(&mut app, point, 0.02);
+        app.world.entity_mut(object).insert(Grabbable);
+        //let rapier pick the ball up before anything queries for it
+        step(&mut app, 1);
+        (app, object)
+    }
+
+    fn track(app: &mut App, hand: Hand, joints: HandJoints) {
+        *app.world.resource_mut::<TrackedHands>().get_mut(hand) = Some(joints);
+    }
+
+    #[test]
+    fn pinch_midpoint_anchor_grabs_off_the_thumb_tip() {
+        let pinch = HandJoints::pinch(Hand::Right);
+        let point = GrabAnchor::PinchMidpoint.point(&pinch);
+        let (mut app, object) = grab_app(
+            GrabConfig {
+                anchor: GrabAnchor::PinchMidpoint,
+                ..default()
+            },
+            point,
+        );
+
+        track(&mut app, Hand::Right, pinch);
+        app.world.run_system_once(grab_objects);
+
+        let held = app.world.get::<Held>(object).unwrap();
+        assert_eq!(held.hand, Hand::Right);
+        assert_eq!(
+            held.anchor_entity,
+            bone_entity(&app, Hand::Right, PhysicsHandBone::ThumbTip)
+        );
+        assert_eq!(app.world.resource::<GrabState>().right, Some(object));
+    }
+
+    #[test]
+    fn held_objects_leave_the_holding_hand_alone() {
+        //a pinch whose palm lines up with the physics palm, and a ball right on it
+        let mut app = hand_app(HandPhysicsConfig::default());
+        let palm = bone_transform(&app, Hand::Right, PhysicsHandBone::Palm).translation;
+        let pinch = HandJoints::pinch(Hand::Right);
+        let offset = palm - GrabAnchor::Palm.point(&pinch);
+        let pinch = pinch.transformed(&HandOrigin(Transform::from_translation(offset)));
+        let object = spawn_ball(&mut app, palm, 0.02);
+        app.world.entity_mut(object).insert(Grabbable);
+
+        let touching = |app: &mut App| {
+            app.world
+                .run_system_once(|hands: PhysicsHands| hands.contacts(Hand::Right))
+                .iter()
+                .any(|(_, entity)| *entity == object)
+        };
+        step(&mut app, 1);
+        assert!(touching(&mut app));
+
+        track(&mut app, Hand::Right, pinch);
+        app.world.run_system_once(grab_objects);
+        step(&mut app, 2);
+        let groups = *app.world.get::<CollisionGroups>(object).unwrap();
+        assert!(!groups.filters.intersects(hand_membership(Hand::Right)));
+        assert!(!touching(&mut app));
+
+        //let go, and it had no groups of its own to go back to
+        track(&mut app, Hand::Right, HandJoints::flat(Hand::Right));
+        app.world.run_system_once(grab_objects);
+        assert!(app.world.get::<CollisionGroups>(object).is_none());
+        assert!(app.world.get::<Held>(object).is_none());
+    }
+
+    // how far a light held object ends up from its grip point after the fingers kick it back
+    // and forth for a few seconds
+    fn grip_drift(config: GrabConfig) -> f32 {
+        let mut world = World::new();
+        world.insert_resource(config);
+        world.init_resource::<Time>();
+        let anchor = world
+            .spawn((GlobalTransform::from_xyz(0.0, 1.0, 0.0), Velocity::zero()))
+            .id();
+        let offset = Vec3::new(0.0, 0.0, -0.03);
+        let object = world
+            .spawn((
+                Held {
+                    hand: Hand::Right,
+                    anchor_entity: anchor,
+                    original_groups: None,
+                    local_offset: offset,
+                },
+                GlobalTransform::from_translation(Vec3::Y + offset),
+                Velocity::zero(),
+            ))
+            .id();
+
+        let delta = 1.0 / 60.0;
+        for frame in 0..240 {
+            let kick = if frame % 2 == 0 { 0.5 } else { -0.5 };
+            world.get_mut::<Velocity>(object).unwrap().linvel.x += kick;
+            world
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_secs_f32(delta));
+            world.run_system_once(stabilize_held_objects);
+
+            let linvel = world.get::<Velocity>(object).unwrap().linvel;
+            let position = world.get::<GlobalTransform>(object).unwrap().translation();
+            *world.get_mut::<GlobalTransform>(object).unwrap() =
+                GlobalTransform::from_translation(position + linvel * delta);
+        }
+
+        let position = world.get::<GlobalTransform>(object).unwrap().translation();
+        position.distance(Vec3::Y + offset)
+    }
+
+    #[test]
+    fn light_held_objects_stay_at_the_grip() {
+        assert!(grip_drift(GrabConfig::default()) < 0.01);
+        let unstabilized = grip_drift(GrabConfig {
+            hold_stiffness: 0.0,
+            hold_damping: 0.0,
+            ..default()
+        });
+        assert!(unstabilized > 0.1);
+    }
+
+    #[test]
+    fn anchor_joint_hangs_off_the_offset() {
+        let mut world = World::new();
+        let bone = world.spawn_empty().id();
+        let tool = world.spawn_empty().id();
+        let offset = Vec3::new(0.0, 0.01, -0.03);
+
+        let joint = anchor_joint(bone, offset, tool);
+        assert_eq!(joint.parent, bone);
+        assert_eq!(joint.data.local_anchor1(), offset);
+        assert_eq!(joint.data.local_anchor2(), Vec3::ZERO);
+    }
+
+    #[test]
+    fn only_the_grab_layer_is_grabbed() {
+        let pinch = HandJoints::pinch(Hand::Right);
+        for (layer, grabbed) in [(Group::GROUP_2, false), (Group::GROUP_10, true)] {
+            let (mut app, object) = grab_app(
+                GrabConfig {
+                    grab_layer: Group::GROUP_10,
+                    ..default()
+                },
+                GrabAnchor::Palm.point(&pinch),
+            );
+            app.world
+                .entity_mut(object)
+                .insert(CollisionGroups::new(layer, Group::ALL));
+            step(&mut app, 1);
+
+            track(&mut app, Hand::Right, pinch);
+            app.world.run_system_once(grab_objects);
+
+            assert_eq!(app.world.get::<Held>(object).is_some(), grabbed);
+        }
+    }
+
+    #[test]
+    fn stuck_bones_hold_the_object_until_the_pinch_opens() {
+        let mut app = hand_app(HandPhysicsConfig::default());
+        //only the sticky grab, the ball isn't picked up by the anchor
+        app.insert_resource(GrabConfig {
+            grab_radius: 0.0,
+            sticky: true,
+            sticky_contact_frames: 2,
+            ..default()
+        });
+        //any pose counts as a pinch, so the hand can hold still on its rest pose
+        app.world.resource_mut::<GestureConfig>().pinch_distance = 1.0;
+        let origin = *app.world.resource::<HandOrigin>();
+        let placement = *app.world.resource::<HandPlacement>();
+        drive(
+            &mut app,
+            Hand::Right,
+            rest_pose(Hand::Right, &origin, &placement),
+        );
+
+        //a ball resting against the top of the index tip
+        let tip = bone_entity(&app, Hand::Right, PhysicsHandBone::IndexTip);
+        let tip_radius = app
+            .world
+            .get::<Collider>(tip)
+            .and_then(|collider| collider.as_ball().map(|ball| ball.radius()))
+            .unwrap();
+        let tip_position = bone_transform(&app, Hand::Right, PhysicsHandBone::IndexTip).translation;
+        let object = spawn_ball(
+            &mut app,
+            tip_position + Vec3::Y * (tip_radius + 0.015 - 0.001),
+            0.015,
+        );
+        app.world.entity_mut(object).insert(Grabbable);
+        step(&mut app, 4);
+        assert_eq!(
+            app.world.get::<StuckTo>(tip).map(|stuck| stuck.0),
+            Some(object)
+        );
+
+        //with gravity it hangs off the fingertip instead of sliding off
+        let offset = |app: &App| {
+            app.world.get::<Transform>(object).unwrap().translation
+                - bone_transform(app, Hand::Right, PhysicsHandBone::IndexTip).translation
+        };
+        let stuck_at = offset(&app);
+        app.world.entity_mut(object).insert(GravityScale(1.0));
+        step(&mut app, 30);
+        assert!(offset(&app).distance(stuck_at) < 0.01);
+
+        app.world.resource_mut::<GestureConfig>().pinch_distance = 0.0;
+        step(&mut app, 1);
+        assert!(app.world.get::<StuckTo>(tip).is_none());
+        assert!(app.world.get::<ImpulseJoint>(tip).is_none());
+    }
+
+    // a hand app holding the right hand still on its rest pose, every pose of it a pinch
+    fn sticky_app(config: HandPhysicsConfig, sticky_contact_frames: u32) -> App {
+        let mut app = hand_app(config);
+        app.insert_resource(GrabConfig {
+            grab_radius: 0.0,
+            sticky: true,
+            sticky_contact_frames,
+            ..default()
+        });
+        app.world.resource_mut::<GestureConfig>().pinch_distance = 1.0;
+        let origin = *app.world.resource::<HandOrigin>();
+        let placement = *app.world.resource::<HandPlacement>();
+        drive(
+            &mut app,
+            Hand::Right,
+            rest_pose(Hand::Right, &origin, &placement),
+        );
+        app
+    }
+
+    // where a ball of `radius` rests on top of the ball collider of `bone`
+    fn resting_on(app: &App, bone: PhysicsHandBone, radius: f32) -> Vec3 {
+        let bone_radius = app
+            .world
+            .get::<Collider>(bone_entity(app, Hand::Right, bone))
+            .and_then(|collider| collider.as_ball().map(|ball| ball.radius()))
+            .unwrap();
+        bone_transform(app, Hand::Right, bone).translation
+            + Vec3::Y * (bone_radius + radius - 0.001)
+    }
+
+    #[test]
+    fn a_released_palm_goes_back_on_its_wrist_joint() {
+        let mut app = sticky_app(
+            HandPhysicsConfig {
+                wrist_palm_joint: true,
+                ..default()
+            },
+            2,
+        );
+        step(&mut app, 1);
+        let wrist = bone_entity(&app, Hand::Right, PhysicsHandBone::Wrist);
+        let palm = bone_entity(&app, Hand::Right, PhysicsHandBone::Palm);
+        let parent = |app: &App| {
+            app.world
+                .get::<ImpulseJoint>(palm)
+                .map(|joint| joint.parent)
+        };
+        assert_eq!(parent(&app), Some(wrist));
+
+        let position = resting_on(&app, PhysicsHandBone::Palm, 0.015);
+        let object = spawn_ball(&mut app, position, 0.015);
+        app.world.entity_mut(object).insert(Grabbable);
+        step(&mut app, 4);
+        assert_eq!(
+            app.world.get::<StuckTo>(palm).map(|stuck| stuck.0),
+            Some(object)
+        );
+        assert_eq!(parent(&app), Some(object));
+
+        //the wrist joint waits for the stick rather than taking its place back
+        step(&mut app, 2);
+        assert_eq!(parent(&app), Some(object));
+
+        app.world.resource_mut::<GestureConfig>().pinch_distance = 0.0;
+        step(&mut app, 2);
+        assert!(app.world.get::<StuckTo>(palm).is_none());
+        assert_eq!(parent(&app), Some(wrist));
+        assert!(app.world.entity(palm).contains::<WristPalmJoint>());
+    }
+
+    #[test]
+    fn only_contact_frames_in_a_row_stick() {
+        let mut app = sticky_app(HandPhysicsConfig::default(), 6);
+        let tip = bone_entity(&app, Hand::Right, PhysicsHandBone::IndexTip);
+        let touching = resting_on(&app, PhysicsHandBone::IndexTip, 0.015);
+        let object = spawn_ball(&mut app, touching, 0.015);
+        app.world.entity_mut(object).insert(Grabbable);
+
+        //held in place every step so the tip can't push it away
+        let hold = |app: &mut App, position: Vec3, steps: u32| {
+            for _ in 0..steps {
+                app.world
+                    .entity_mut(object)
+                    .insert((Transform::from_translation(position), Velocity::zero()));
+                step(app, 1);
+            }
+        };
+
+        hold(&mut app, touching, 5);
+        hold(&mut app, touching + Vec3::Y * 0.2, 2);
+        hold(&mut app, touching, 5);
+        assert!(app.world.get::<StuckTo>(tip).is_none());
+
+        hold(&mut app, touching, 5);
+        assert_eq!(
+            app.world.get::<StuckTo>(tip).map(|stuck| stuck.0),
+            Some(object)
+        );
+    }
+
+    #[test]
+    fn the_throw_estimator_catches_the_flick_peak() {
+        use std::f32::consts::PI;
+
+        //a 0.2s flick along x at up to 4 m/s, tracked at 90hz and let go at its fastest
+        let (peak, duration, delta) = (4.0, 0.2, 1.0 / 90.0);
+        let position = |time: f32| peak * duration / PI * (1.0 - (PI * time / duration).cos());
+
+        let mut estimator = ThrowEstimator::default();
+        //what a velocity taken off a target smoothed like the wrist would see
+        let follow = 1.0 - 0.8;
+        let mut smoothed = position(0.0);
+        let mut smoothed_velocity = 0.0;
+        for frame in 0..=9 {
+            let time = frame as f32 * delta;
+            let x = position(time);
+            estimator.push(Hand::Right, time as f64, Vec3::X * x, 0.05);
+
+            let next = smoothed + (x - smoothed) * follow;
+            smoothed_velocity = (next - smoothed) / delta;
+            smoothed = next;
+        }
+
+        let estimated = estimator.velocity(Hand::Right).unwrap().x;
+        assert!((estimated - peak).abs() < (smoothed_velocity - peak).abs());
+        assert!((estimated - peak).abs() < peak * 0.1);
+    }
+}
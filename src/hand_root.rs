@@ -0,0 +1,95 @@
+use bevy::prelude::*;
+use bevy_oxr::xr_input::Hand;
+
+// parent of every physics bone of one hand when HandPhysicsConfig::spawn_hand_roots is set
+#[derive(Component, Debug, Clone, Copy)]
+pub struct HandRoot {
+    pub hand: Hand,
+}
+
+pub fn spawn_hand_root(commands: &mut Commands, hand: Hand) -> Entity {
+    commands
+        .spawn((
+            Name::new(format!("{:?} Hand Root", hand)),
+            SpatialBundle::default(),
+            HandRoot { hand },
+        ))
+        .id()
+}
+
+pub fn set_hand_visible(
+    root_query: &mut Query<(&HandRoot, &mut Visibility)>,
+    hand: Hand,
+    visible: bool,
+) {
+    for (root, mut visibility) in root_query.iter_mut() {
+        if root.hand != hand {
+            continue;
+        }
+
+        *visibility = if visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+pub fn translate_hand(
+    root_query: &mut Query<(&HandRoot, &mut Transform)>,
+    hand: Hand,
+    offset: Vec3,
+) {
+    for (root, mut transform) in root_query.iter_mut() {
+        if root.hand == hand {
+            transform.translation += offset;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::config::HandPhysicsConfig;
+    use crate::constants::PhysicsHandsResource;
+    use crate::test_utils::hand_app;
+
+    #[test]
+    fn every_bone_hangs_off_its_hand_root() {
+        let mut app = hand_app(HandPhysicsConfig {
+            spawn_hand_roots: true,
+            ..Default::default()
+        });
+
+        let mut roots = app.world.query::<(Entity, &HandRoot)>();
+        let roots: Vec<(Entity, Hand)> = roots
+            .iter(&app.world)
+            .map(|(entity, root)| (entity, root.hand))
+            .collect();
+        assert_eq!(roots.len(), 2);
+
+        let physics_hands = app.world.resource::<PhysicsHandsResource>();
+        for (root, hand) in roots.iter() {
+            for bone in physics_hands.entities(*hand) {
+                assert_eq!(
+                    app.world.get::<Parent>(bone).map(|parent| parent.get()),
+                    Some(*root)
+                );
+            }
+        }
+
+        app.world
+            .run_system_once(|mut root_query: Query<(&HandRoot, &mut Visibility)>| {
+                set_hand_visible(&mut root_query, Hand::Left, false)
+            });
+        for (root, hand) in roots {
+            let expected = match hand {
+                Hand::Left => Visibility::Hidden,
+                Hand::Right => Visibility::Inherited,
+            };
+            assert_eq!(app.world.get::<Visibility>(root), Some(&expected));
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+use bevy_oxr::xr_input::Hand;
+
+use crate::constants::PhysicsHandBone;
+
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct DominantHand(pub Hand);
+
+impl Default for DominantHand {
+    fn default() -> Self {
+        Self(Hand::Right)
+    }
+}
+
+// a hand named by role rather than side, resolved through DominantHand
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum HandRole {
+    Dominant,
+    OffHand,
+}
+
+pub fn other_hand(hand: Hand) -> Hand {
+    match hand {
+        Hand::Left => Hand::Right,
+        Hand::Right => Hand::Left,
+    }
+}
+
+// the same bone on the other hand, bones aren't sided so only the hand flips
+pub fn opposite_hand_bone(hand: Hand, bone: PhysicsHandBone) -> (Hand, PhysicsHandBone) {
+    (other_hand(hand), bone)
+}
+
+// `position` reflected across the YZ plane of `mirror`, e.g. the head transform, which is where
+// the other hand's copy of a point would sit in a symmetric pose
+pub fn mirrored_position(position: Vec3, mirror: &Transform) -> Vec3 {
+    let local = mirror.rotation.inverse() * (position - mirror.translation);
+    mirror.translation + mirror.rotation * Vec3::new(-local.x, local.y, local.z)
+}
+
+impl DominantHand {
+    pub fn dominant(&self) -> Hand {
+        self.0
+    }
+
+    pub fn off_hand(&self) -> Hand {
+        other_hand(self.0)
+    }
+
+    pub fn resolve(&self, role: HandRole) -> Hand {
+        match role {
+            HandRole::Dominant => self.dominant(),
+            HandRole::OffHand => self.off_hand(),
+        }
+    }
+
+    pub fn role_of(&self, hand: Hand) -> HandRole {
+        if hand == self.0 {
+            HandRole::Dominant
+        } else {
+            HandRole::OffHand
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roles_resolve_through_the_dominant_hand() {
+        let left_handed = DominantHand(Hand::Left);
+        assert_eq!(left_handed.resolve(HandRole::Dominant), Hand::Left);
+        assert_eq!(left_handed.resolve(HandRole::OffHand), Hand::Right);
+        assert_eq!(left_handed.role_of(Hand::Right), HandRole::OffHand);
+        assert_eq!(DominantHand::default().dominant(), Hand::Right);
+    }
+
+    #[test]
+    fn the_other_hand_has_the_same_bone_mirrored() {
+        assert_eq!(
+            opposite_hand_bone(Hand::Left, PhysicsHandBone::IndexTip),
+            (Hand::Right, PhysicsHandBone::IndexTip)
+        );
+        assert_eq!(
+            opposite_hand_bone(Hand::Right, PhysicsHandBone::Palm),
+            (Hand::Left, PhysicsHandBone::Palm)
+        );
+
+        //reflected across the head's own side to side axis, wherever it faces
+        let head = Transform::from_xyz(0.0, 1.6, 0.0).with_rotation(Quat::from_rotation_y(0.5));
+        let left = head.transform_point(Vec3::new(-0.2, -0.3, -0.4));
+        let right = mirrored_position(left, &head);
+        let expected = head.transform_point(Vec3::new(0.2, -0.3, -0.4));
+        assert!(right.distance(expected) < 1e-5);
+        assert!(mirrored_position(right, &head).distance(left) < 1e-5);
+    }
+}
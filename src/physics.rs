@@ -0,0 +1,241 @@
+use std::time::Duration;
+
+use bevy::{
+    ecs::schedule::ScheduleLabel, prelude::*, time::TimeSystem, transform::TransformSystem,
+};
+use bevy_rapier3d::plugin::{RapierConfiguration, TimestepMode};
+use bevy_rapier3d::prelude::*;
+
+use crate::config::{BoneBodyType, HandPhysicsConfig};
+use crate::constants::{PhysicsHandBone, FIXED_TIMESTEP};
+
+// A label for our new Schedule!
+#[derive(ScheduleLabel, Debug, Hash, PartialEq, Eq, Clone)]
+pub struct PhysicsSchedule;
+
+// lets you freeze the physics schedule and advance it one fixed step at a time while debugging
+#[derive(Resource, Debug)]
+pub struct PhysicsStepControl {
+    pub paused: bool,
+    pub step_once: bool,
+    // after a stalled frame the fixed loop wants to catch up on every step it missed, which only
+    // makes the next frame slower. virtual time advances by at most this many steps a frame, the
+    // rest of a stall is dropped rather than simulated
+    pub max_steps_per_frame: u32,
+}
+
+impl Default for PhysicsStepControl {
+    fn default() -> Self {
+        Self {
+            paused: false,
+            step_once: false,
+            max_steps_per_frame: 4,
+        }
+    }
+}
+
+// adds rapier without its default system setup and runs its systems from our own fixed step schedule,
+// the hands are driven from FixedUpdate so rapier has to step at the same rate
+pub fn add_rapier_physics(app: &mut App) {
+    app.add_plugins(RapierPhysicsPlugin::<NoUserData>::default().with_default_system_setup(false));
+
+    app.configure_sets(
+        PostUpdate,
+        (
+            PhysicsSet::SyncBackend,
+            PhysicsSet::StepSimulation,
+            PhysicsSet::Writeback,
+        )
+            .chain()
+            .before(TransformSystem::TransformPropagate),
+    );
+
+    //configure rapier sets
+    let mut physics_schedule = Schedule::new(PhysicsSchedule);
+
+    physics_schedule.configure_sets(
+        (
+            PhysicsSet::SyncBackend,
+            PhysicsSet::StepSimulation,
+            PhysicsSet::Writeback,
+        )
+            .chain()
+            .before(TransformSystem::TransformPropagate),
+    );
+
+    //add rapier systems
+    physics_schedule.add_systems((
+        RapierPhysicsPlugin::<NoUserData>::get_systems(PhysicsSet::SyncBackend)
+            .in_set(PhysicsSet::SyncBackend),
+        RapierPhysicsPlugin::<NoUserData>::get_systems(PhysicsSet::StepSimulation)
+            .in_set(PhysicsSet::StepSimulation),
+        RapierPhysicsPlugin::<NoUserData>::get_systems(PhysicsSet::Writeback)
+            .in_set(PhysicsSet::Writeback),
+    ));
+    app.add_schedule(physics_schedule) // configure our fixed timestep schedule to run at the rate we want
+        .insert_resource(Time::<Fixed>::from_duration(Duration::from_secs_f32(
+            FIXED_TIMESTEP,
+        )))
+        .init_resource::<PhysicsStepControl>()
+        .add_systems(First, limit_catch_up.after(TimeSystem))
+        .add_systems(FixedUpdate, run_physics_schedule)
+        .add_systems(Startup, configure_physics);
+}
+
+// runs right after bevy advanced the clocks, so the warning is about the frame that just started
+// and the new limit holds from the next one on
+fn limit_catch_up(
+    step_control: Res<PhysicsStepControl>,
+    fixed_time: Res<Time<Fixed>>,
+    real_time: Res<Time<Real>>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+) {
+    //paused, the steps are dropped on purpose and nothing fell behind
+    if !step_control.paused && !virtual_time.is_paused() {
+        let wanted = real_time
+            .delta()
+            .mul_f64(virtual_time.effective_speed_f64());
+        if wanted > virtual_time.max_delta() {
+            warn!(
+                "physics fell behind, dropped {:?} of a {:?} frame",
+                wanted - virtual_time.max_delta(),
+                wanted
+            );
+        }
+    }
+
+    virtual_time.set_max_delta(fixed_time.timestep() * step_control.max_steps_per_frame.max(1));
+}
+
+fn run_physics_schedule(world: &mut World) {
+    let should_run = {
+        let mut step_control = world.resource_mut::<PhysicsStepControl>();
+
+        if !step_control.paused {
+            true
+        } else if step_control.step_once {
+            step_control.step_once = false;
+            true
+        } else {
+            false
+        }
+    };
+
+    if should_run {
+        world.run_schedule(PhysicsSchedule);
+    }
+}
+
+fn configure_physics(mut rapier_config: ResMut<RapierConfiguration>) {
+    rapier_config.timestep_mode = TimestepMode::Fixed {
+        dt: FIXED_TIMESTEP,
+        substeps: 1,
+    }
+}
+
+// upward force cancelling each dynamic bone's weight, see HandPhysicsConfig::gravity_compensation
+pub fn compensate_gravity(
+    mut commands: Commands,
+    config: Res<HandPhysicsConfig>,
+    rapier_config: Res<RapierConfiguration>,
+    mut bone_query: Query<
+        (
+            Entity,
+            Option<&ReadMassProperties>,
+            Option<&mut ExternalForce>,
+        ),
+        With<PhysicsHandBone>,
+    >,
+) {
+    let enabled = config.gravity_compensation && config.body_type == BoneBodyType::Dynamic;
+
+    for (entity, mass_properties, external_force) in bone_query.iter_mut() {
+        let force = match mass_properties {
+            Some(mass_properties) if enabled => -rapier_config.gravity * mass_properties.get().mass,
+            _ => Vec3::ZERO,
+        };
+
+        match external_force {
+            Some(mut external_force) => external_force.force = force,
+            None if enabled => {
+                //rapier only fills in the mass once it is asked to
+                commands.entity(entity).insert((
+                    ReadMassProperties::default(),
+                    ExternalForce {
+                        force,
+                        ..Default::default()
+                    },
+                ));
+            }
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_oxr::xr_input::Hand;
+
+    use super::*;
+    use crate::clock::{advance_time, run_fixed_steps, TestClock};
+    use crate::pose::{rest_pose, HandOrigin, HandPlacement};
+    use crate::test_utils::{bone_transform, drive_once, hand_app};
+
+    #[test]
+    fn paused_physics_only_advances_on_step_once() {
+        let mut app = hand_app(HandPhysicsConfig::default());
+
+        app.world.resource_mut::<PhysicsStepControl>().paused = true;
+        assert_eq!(run_fixed_steps(&mut app, 3), 0);
+
+        app.world.resource_mut::<PhysicsStepControl>().step_once = true;
+        assert_eq!(run_fixed_steps(&mut app, 3), 1);
+
+        app.world.resource_mut::<PhysicsStepControl>().paused = false;
+        assert_eq!(run_fixed_steps(&mut app, 3), 3);
+    }
+
+    // how far the right palm hangs below its target after holding a still pose for a while
+    fn palm_sag(gravity_compensation: bool) -> f32 {
+        let mut app = hand_app(HandPhysicsConfig {
+            body_type: BoneBodyType::Dynamic,
+            gravity_compensation,
+            ..default()
+        });
+        let origin = *app.world.resource::<HandOrigin>();
+        let placement = *app.world.resource::<HandPlacement>();
+        let left = rest_pose(Hand::Left, &origin, &placement);
+        let right = rest_pose(Hand::Right, &origin, &placement);
+
+        for _ in 0..30 {
+            drive_once(&mut app, left, right);
+        }
+        let palm = bone_transform(&app, Hand::Right, PhysicsHandBone::Palm).translation;
+        right.joint(PhysicsHandBone::Palm).position.y - palm.y
+    }
+
+    #[test]
+    fn a_stalled_frame_runs_at_most_max_steps() {
+        let mut app = hand_app(HandPhysicsConfig::default());
+        app.world
+            .resource_mut::<PhysicsStepControl>()
+            .max_steps_per_frame = 3;
+        //the new limit holds from the frame after it was set
+        run_fixed_steps(&mut app, 1);
+
+        let timestep = app.world.resource::<Time<Fixed>>().timestep();
+        let before = app.world.resource::<TestClock>().physics_steps;
+        advance_time(&mut app, timestep * 10);
+        assert_eq!(app.world.resource::<TestClock>().physics_steps - before, 3);
+
+        //the stall is dropped, not carried over into the next frames
+        assert_eq!(run_fixed_steps(&mut app, 2), 2);
+    }
+
+    #[test]
+    fn gravity_compensation_keeps_dynamic_bones_up() {
+        let sag = palm_sag(false);
+        assert!(sag > 5e-4);
+        assert!(palm_sag(true) < sag * 0.5);
+    }
+}
@@ -0,0 +1,352 @@
+use bevy::{ecs::system::EntityCommands, prelude::*};
+use bevy_oxr::xr_input::Hand;
+use bevy_rapier3d::prelude::*;
+
+use crate::buttons::{press_buttons, ButtonPress};
+use crate::calibration::{
+    calibrate_scale, handle_recalibrate_requests, HandScale, RecalibrateRequest,
+    RecalibrationComplete, ScaleCalibration,
+};
+use crate::colliders::{
+    apply_bone_disabled, apply_live_joint_radii, boost_punch_colliders, drive_palm_boxes,
+    drive_wrists, follow_palm_sensors, follow_thumb_webs, joint_palms_to_wrists,
+    report_hand_proximity, spawn_palm_sensors, spawn_thumb_webs, update_collider_activation,
+    HandCollisionEnabled, HandProximity, HandProximityEnded,
+};
+use crate::config::HandPhysicsConfig;
+use crate::constants::{
+    spawn_hand_entities, spawn_physics_hands, update_physics_hands, PhysicsHandBone,
+    PhysicsHandsResource,
+};
+#[cfg(feature = "debug")]
+use crate::debug::{
+    bone_colors_enabled, color_bones_by_collision_group, color_bones_by_velocity, debug_enabled,
+    draw_bone_targets, group_colors_enabled, log_collision_groups, restore_bone_colors,
+    velocity_colors_enabled, MyRoundGizmos,
+};
+use crate::debug::{HandDebugConfig, LogCollisionGroups};
+use crate::driven::{follow_wrist_anchor, DrivenPose};
+use crate::gestures::{
+    detect_finger_gun_fire, detect_hands_clap, detect_shakes, update_current_gestures,
+    CurrentGestures, FingerGunFire, GestureConfig, HandsClap, ShakeGesture,
+};
+use crate::grab::{
+    grab_objects, sample_throw_velocity, stabilize_held_objects, stick_contacting_bones,
+    GrabConfig, GrabState, ThrowEstimator,
+};
+use crate::handedness::DominantHand;
+use crate::physics::{add_rapier_physics, compensate_gravity};
+use crate::pose::{reset_pose, HandOrigin, HandPlacement, ResetPose};
+use crate::reactions::{trigger_bone_contacts, BoneContact};
+use crate::recording::{record_hands, HandRecorder};
+use crate::targets::{
+    blend_joint_visuals, record_target_history, smooth_fingers, stabilize_wrist, FingerSmoothing,
+    WristSmoothing,
+};
+use crate::tips::merge_close_fingertips;
+use crate::tracking::{
+    any_hand_tracked, collect_tracked_hands, detect_tracking_transitions, enough_joints_tracked,
+    hold_pose_on_tracking_loss, reset_accumulators_on_resume, spawn_at_startup,
+    update_last_good_pose, update_tracked_velocities, HandTrackingLost, HandTrackingStarted,
+    LastGoodPose, TrackedHands, TrackedVelocities,
+};
+
+pub type BoneSpawnHook = fn(&mut EntityCommands, Hand, PhysicsHandBone);
+
+// called for every physics bone right after it is spawned, so users can insert their own components
+#[derive(Resource, Default, Clone, Copy)]
+pub struct OnBoneSpawn(pub Option<BoneSpawnHook>);
+
+// Input reads and filters tracking, Update drives the bones, PostUpdate reacts to the result
+// (grabs, gestures, contacts, debug). configured in the same order in PreUpdate, Update and
+// FixedUpdate, the bones themselves are driven in FixedUpdate
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HandPhysicsSet {
+    Input,
+    Update,
+    PostUpdate,
+}
+
+fn hand_physics_sets() -> impl IntoSystemSetConfigs {
+    (
+        HandPhysicsSet::Input,
+        HandPhysicsSet::Update,
+        HandPhysicsSet::PostUpdate,
+    )
+        .chain()
+}
+
+pub struct XrHandPhysicsPlugin {
+    pub on_bone_spawn: Option<BoneSpawnHook>,
+    // add rapier with our fixed step schedule when the app doesn't register it itself
+    pub add_rapier: bool,
+}
+
+impl Default for XrHandPhysicsPlugin {
+    fn default() -> Self {
+        Self {
+            on_bone_spawn: None,
+            add_rapier: true,
+        }
+    }
+}
+
+impl Plugin for XrHandPhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        if self.add_rapier && !app.is_plugin_added::<RapierPhysicsPlugin<NoUserData>>() {
+            add_rapier_physics(app);
+        }
+
+        //insert a HandDebugConfig before adding the plugin to get the debug gizmos
+        #[cfg(feature = "debug")]
+        let debug_gizmos = app
+            .world
+            .get_resource::<HandDebugConfig>()
+            .is_some_and(|debug_config| debug_config.enabled);
+        #[cfg(feature = "debug")]
+        if debug_gizmos {
+            app.init_gizmo_group::<MyRoundGizmos>();
+        }
+
+        app.configure_sets(PreUpdate, hand_physics_sets())
+            .configure_sets(Update, hand_physics_sets())
+            .configure_sets(
+                FixedUpdate,
+                hand_physics_sets().before(PhysicsSet::SyncBackend),
+            );
+
+        app.init_resource::<HandPhysicsConfig>()
+            .init_resource::<HandDebugConfig>()
+            .init_resource::<DominantHand>()
+            .init_resource::<HandOrigin>()
+            .init_resource::<HandPlacement>()
+            .init_resource::<GestureConfig>()
+            .init_resource::<CurrentGestures>()
+            .init_resource::<GrabConfig>()
+            .init_resource::<GrabState>()
+            .init_resource::<ThrowEstimator>()
+            .init_resource::<HandRecorder>()
+            .init_resource::<HandScale>()
+            .init_resource::<ScaleCalibration>()
+            .init_resource::<TrackedHands>()
+            .init_resource::<TrackedVelocities>()
+            .init_resource::<LastGoodPose>()
+            .init_resource::<DrivenPose>()
+            .init_resource::<WristSmoothing>()
+            .init_resource::<FingerSmoothing>()
+            .init_resource::<HandCollisionEnabled>()
+            .insert_resource(OnBoneSpawn(self.on_bone_spawn))
+            .add_event::<HandsClap>()
+            .add_event::<ShakeGesture>()
+            .add_event::<FingerGunFire>()
+            .add_event::<ResetPose>()
+            .add_event::<ButtonPress>()
+            .add_event::<LogCollisionGroups>()
+            .add_event::<HandTrackingLost>()
+            .add_event::<HandTrackingStarted>()
+            .add_event::<BoneContact>()
+            .add_event::<RecalibrateRequest>()
+            .add_event::<RecalibrationComplete>()
+            .add_event::<HandProximity>()
+            .add_event::<HandProximityEnded>()
+            .add_systems(
+                Startup,
+                (
+                    spawn_hand_entities.before(spawn_physics_hands),
+                    spawn_physics_hands.run_if(spawn_at_startup),
+                    spawn_palm_sensors,
+                    spawn_thumb_webs,
+                ),
+            )
+            .add_systems(
+                PreUpdate,
+                (
+                    (follow_wrist_anchor, stabilize_wrist, smooth_fingers)
+                        .chain()
+                        .before(record_target_history),
+                    record_target_history,
+                    (
+                        collect_tracked_hands,
+                        update_last_good_pose,
+                        detect_tracking_transitions,
+                        hold_pose_on_tracking_loss,
+                        update_tracked_velocities,
+                        reset_accumulators_on_resume,
+                    )
+                        .chain()
+                        .after(follow_wrist_anchor),
+                    //with min_tracked_joints set, spawn once tracking is good enough instead.
+                    //counted before a partly tracked hand is swapped for its fallback pose
+                    spawn_physics_hands
+                        .run_if(
+                            not(resource_exists::<PhysicsHandsResource>)
+                                .and_then(enough_joints_tracked),
+                        )
+                        .after(collect_tracked_hands)
+                        .before(hold_pose_on_tracking_loss),
+                )
+                    .in_set(HandPhysicsSet::Input),
+            )
+            .add_systems(
+                Update,
+                (
+                    record_hands,
+                    handle_recalibrate_requests.before(calibrate_scale),
+                    calibrate_scale,
+                )
+                    .in_set(HandPhysicsSet::Input),
+            )
+            .add_systems(
+                Update,
+                (
+                    sample_throw_velocity.before(grab_objects),
+                    grab_objects,
+                    stick_contacting_bones,
+                    detect_hands_clap,
+                    update_current_gestures,
+                    detect_shakes,
+                    detect_finger_gun_fire,
+                    press_buttons,
+                    trigger_bone_contacts,
+                    report_hand_proximity,
+                    blend_joint_visuals,
+                )
+                    .in_set(HandPhysicsSet::PostUpdate),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    reset_pose.in_set(HandPhysicsSet::Input),
+                    (
+                        update_physics_hands.run_if(any_hand_tracked),
+                        merge_close_fingertips,
+                        update_collider_activation,
+                        apply_bone_disabled,
+                        compensate_gravity,
+                        apply_live_joint_radii.after(update_physics_hands),
+                        boost_punch_colliders.after(apply_live_joint_radii),
+                        follow_thumb_webs.after(update_physics_hands),
+                        drive_palm_boxes,
+                        drive_wrists,
+                        joint_palms_to_wrists,
+                    )
+                        .in_set(HandPhysicsSet::Update),
+                    (stabilize_held_objects, follow_palm_sensors)
+                        .in_set(HandPhysicsSet::PostUpdate),
+                ),
+            );
+
+        //LogCollisionGroups stays registered so sending it compiles either way,
+        //without the feature nothing reads it
+        #[cfg(feature = "debug")]
+        app.add_systems(
+            Update,
+            (
+                draw_bone_targets.run_if(debug_enabled),
+                color_bones_by_velocity.run_if(velocity_colors_enabled),
+                color_bones_by_collision_group.run_if(group_colors_enabled),
+                restore_bone_colors.run_if(not(bone_colors_enabled)),
+                log_collision_groups,
+            )
+                .in_set(HandPhysicsSet::PostUpdate),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::get_default_hand;
+    use crate::physics::PhysicsSchedule;
+    use crate::pose::HandOrigin;
+    use crate::test_utils::{bone_entity, drive, hand_app, hand_app_with, step};
+
+    #[derive(Component)]
+    struct Tagged(Hand, PhysicsHandBone);
+
+    fn tag(bone_commands: &mut EntityCommands, hand: Hand, bone: PhysicsHandBone) {
+        bone_commands.insert(Tagged(hand, bone));
+    }
+
+    #[test]
+    fn spawn_hook_runs_for_every_bone() {
+        let mut app = hand_app_with(
+            HandPhysicsConfig::default(),
+            XrHandPhysicsPlugin {
+                on_bone_spawn: Some(tag),
+                ..Default::default()
+            },
+        );
+
+        let mut bones = app.world.query::<(&Tagged, &Hand, &PhysicsHandBone)>();
+        let tagged: Vec<_> = bones.iter(&app.world).collect();
+        assert_eq!(tagged.len(), 2 * 26);
+        for (tagged, hand, bone) in tagged {
+            assert_eq!((tagged.0, tagged.1), (*hand, *bone));
+        }
+    }
+
+    // rapier step systems registered anywhere rapier could have put them
+    fn step_simulation_systems(rapier_added_first: bool) -> usize {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        if rapier_added_first {
+            app.add_plugins(RapierPhysicsPlugin::<NoUserData>::default());
+        }
+        app.add_plugins(XrHandPhysicsPlugin::default());
+
+        let count = |schedule: Option<&Schedule>| {
+            schedule.map_or(0, |schedule| {
+                schedule
+                    .graph()
+                    .systems()
+                    .filter(|(_, system, _)| system.name().contains("step_simulation"))
+                    .count()
+            })
+        };
+        count(app.get_schedule(PostUpdate)) + count(app.get_schedule(PhysicsSchedule))
+    }
+
+    #[test]
+    fn rapier_is_added_once_either_way() {
+        assert_eq!(step_simulation_systems(false), 1);
+        assert_eq!(step_simulation_systems(true), 1);
+    }
+
+    #[derive(Resource, Default)]
+    struct SeenVelocity(Vec3);
+
+    #[test]
+    fn systems_after_the_update_set_see_the_driven_bones() {
+        let mut app = hand_app(HandPhysicsConfig::default());
+        let bone = bone_entity(&app, Hand::Right, PhysicsHandBone::IndexProximal);
+        app.init_resource::<SeenVelocity>().add_systems(
+            FixedUpdate,
+            (move |bones: Query<&Velocity>, mut seen: ResMut<SeenVelocity>| {
+                seen.0 = bones.get(bone).unwrap().linvel;
+            })
+            .after(HandPhysicsSet::Update),
+        );
+
+        let raised = get_default_hand(&Hand::Right)
+            .transformed(&HandOrigin(Transform::from_xyz(0.0, 0.1, 0.0)));
+        drive(&mut app, Hand::Right, raised);
+        //the first step only initializes the bones, the second is the first one they move in
+        step(&mut app, 2);
+
+        assert!(app.world.resource::<SeenVelocity>().0.length() > 0.0);
+    }
+
+    #[test]
+    fn debug_systems_only_exist_with_the_feature() {
+        let app = hand_app(HandPhysicsConfig::default());
+        let debug_systems = app
+            .get_schedule(Update)
+            .unwrap()
+            .graph()
+            .systems()
+            .filter(|(_, system, _)| system.name().contains("::debug::"))
+            .count();
+        assert_eq!(debug_systems > 0, cfg!(feature = "debug"));
+    }
+}
@@ -0,0 +1,911 @@
+use bevy::{ecs::query::QueryFilter, prelude::*};
+use bevy_oxr::xr_input::{
+    hands::common::{HandBoneRadius, HandResource},
+    Hand,
+};
+use bevy_rapier3d::dynamics::Velocity;
+
+use crate::calibration::HandScale;
+use crate::constants::{
+    get_default_hand, get_hand_resource_entity, BoneInitState, Finger, HandJoint, HandJoints,
+    NameToHandJoint, PhysicsHandBone, BONE_CONNECTIONS,
+};
+
+// where the default poses get placed in the world, identity keeps the recorded coordinates
+#[derive(Resource, Debug, Clone, Copy, Default)]
+pub struct HandOrigin(pub Transform);
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum CurlPreset {
+    // the recorded pose as is
+    Recorded,
+    Relaxed,
+    Fist,
+}
+
+impl CurlPreset {
+    // 0 leaves the fingers alone, 1 bends every finger joint a right angle into the palm
+    pub fn amount(&self) -> f32 {
+        match self {
+            CurlPreset::Recorded => 0.0,
+            CurlPreset::Relaxed => 0.3,
+            CurlPreset::Fist => 1.0,
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct HandPlacement {
+    // distance in front of the camera to put the centroid of both hands
+    pub distance: f32,
+    // use exactly this HandOrigin instead of placing the hands relative to the camera,
+    // for screenshots and docs that need the same hands every run
+    pub origin_override: Option<Transform>,
+    pub curl: CurlPreset,
+}
+
+impl Default for HandPlacement {
+    fn default() -> Self {
+        Self {
+            distance: 0.35,
+            origin_override: None,
+            curl: CurlPreset::Recorded,
+        }
+    }
+}
+
+impl HandPlacement {
+    // the override as is, otherwise in front of the camera
+    pub fn origin(&self, camera: &Transform, default_centroid: Vec3) -> HandOrigin {
+        match self.origin_override {
+            Some(origin) => HandOrigin(origin),
+            None => HandOrigin::in_front_of_camera(camera, self.distance, default_centroid),
+        }
+    }
+}
+
+// the default pose curled to the placement preset and moved to the origin, what hands spawn and reset to
+pub fn rest_pose(hand: Hand, origin: &HandOrigin, placement: &HandPlacement) -> HandJoints {
+    get_default_hand(&hand)
+        .curled(placement.curl.amount())
+        .transformed(origin)
+}
+
+impl HandOrigin {
+    // place the `default_centroid` of the rest poses `distance` along the camera forward,
+    // rotating the poses with the camera so they keep facing it
+    pub fn in_front_of_camera(camera: &Transform, distance: f32, default_centroid: Vec3) -> Self {
+        let target = camera.translation + *camera.forward() * distance;
+        let rotation = camera.rotation;
+
+        Self(Transform {
+            translation: target - rotation * default_centroid,
+            rotation,
+            ..Default::default()
+        })
+    }
+
+    pub fn apply(&self, joint: &HandJoint) -> HandJoint {
+        HandJoint {
+            position: self.0.transform_point(joint.position),
+            orientation: self.0.rotation * joint.orientation,
+            ..*joint
+        }
+    }
+}
+
+// tracking occasionally hands out NaN or slightly non unit quaternions, looking_at and the velocity
+// math downstream assume unit ones, so normalize and reject anything that can't be
+pub fn sanitize_orientation(rotation: Quat) -> Option<Quat> {
+    if !rotation.is_finite() || rotation.length_squared() < 1e-6 {
+        return None;
+    }
+    Some(rotation.normalize())
+}
+
+impl HandJoint {
+    pub fn sanitized(&self) -> Option<HandJoint> {
+        Some(HandJoint {
+            orientation: sanitize_orientation(self.orientation)?,
+            ..*self
+        })
+    }
+}
+
+impl HandJoints {
+    // None as soon as one joint is invalid, the frame should be skipped as a whole
+    pub fn sanitized(&self) -> Option<HandJoints> {
+        let mut inner = self.inner;
+        for joint in inner.iter_mut() {
+            *joint = joint.sanitized()?;
+        }
+        Some(HandJoints { inner })
+    }
+
+    // bend every finger joint from the proximal on toward the palm, around the joint's own x axis
+    pub fn curled(&self, amount: f32) -> HandJoints {
+        let mut joints = *self;
+        if amount <= 0.0 {
+            return joints;
+        }
+
+        for finger in Finger::ALL.iter() {
+            joints = joints.curled_finger(*finger, amount);
+        }
+
+        joints
+    }
+
+    // curled for a single finger, a negative amount straightens it back out
+    fn curled_finger(&self, finger: Finger, amount: f32) -> HandJoints {
+        let mut joints = *self;
+        let bones = finger.bones();
+        //the thumb bends the other way round the hand and has one joint less, go easy on it
+        let angle = match finger {
+            Finger::Thumb => -amount * std::f32::consts::FRAC_PI_4,
+            _ => -amount * std::f32::consts::FRAC_PI_2,
+        };
+
+        for pivot_index in 1..bones.len() - 1 {
+            let pivot = *joints.joint(bones[pivot_index]);
+            let rotation = Quat::from_axis_angle(pivot.orientation * Vec3::X, angle);
+
+            for bone in bones[pivot_index..].iter() {
+                let joint = &mut joints.inner[bone.index()];
+                joint.position = pivot.position + rotation * (joint.position - pivot.position);
+                joint.orientation = rotation * joint.orientation;
+            }
+        }
+
+        joints
+    }
+
+    // curl `finger` within `curl_range` until its tip is as far from the joint at `base_index` in
+    // its chain as `point` is, then swing everything from that joint on so the tip points at it.
+    // neither changes a bone length, a point out of reach is fallen short of
+    fn reached_for(
+        &self,
+        finger: Finger,
+        base_index: usize,
+        point: Vec3,
+        curl_range: (f32, f32),
+    ) -> HandJoints {
+        let bones = &finger.bones()[base_index..];
+        let base = self.joint(bones[0]).position;
+        let wanted = base.distance(point);
+        let reach = |joints: &HandJoints| base.distance(joints.joint(finger.tip()).position);
+
+        //a finger only gets shorter the more it curls, bisect the curl
+        let (mut low, mut high) = curl_range;
+        for _ in 0..32 {
+            let middle = (low + high) / 2.0;
+            if reach(&self.curled_finger(finger, middle)) > wanted {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        let mut joints = self.curled_finger(finger, (low + high) / 2.0);
+
+        let tip = joints.joint(finger.tip()).position;
+        let swing = Quat::from_rotation_arc((tip - base).normalize(), (point - base).normalize());
+        for bone in bones.iter() {
+            let joint = &mut joints.inner[bone.index()];
+            joint.position = base + swing * (joint.position - base);
+            joint.orientation = swing * joint.orientation;
+        }
+
+        joints
+    }
+
+    // the default hand with every finger laid straight out along its metacarpal, segment lengths kept
+    pub fn flat(hand: Hand) -> HandJoints {
+        let mut joints = get_default_hand(&hand);
+
+        for finger in Finger::ALL.iter() {
+            let bones = finger.bones();
+            let base = *joints.joint(bones[1]);
+            let direction = (base.position - joints.joint(bones[0]).position).normalize_or_zero();
+            if direction == Vec3::ZERO {
+                continue;
+            }
+
+            let mut position = base.position;
+            for pair in bones[1..].windows(2) {
+                let length = joints
+                    .joint(pair[0])
+                    .position
+                    .distance(joints.joint(pair[1]).position);
+                position += direction * length;
+
+                let joint = &mut joints.inner[pair[1].index()];
+                joint.position = position;
+                joint.orientation = base.orientation;
+            }
+        }
+
+        joints
+    }
+
+    pub fn fist(hand: Hand) -> HandJoints {
+        Self::flat(hand).curled(CurlPreset::Fist.amount())
+    }
+
+    // relaxed hand with the thumb and index tips brought together. the thumb reaches as far as it
+    // can toward halfway between them and the index meets it there, both chains bend and swing as
+    // a whole so the bones keep the lengths their capsules were spawned with
+    pub fn pinch(hand: Hand) -> HandJoints {
+        let relaxed = Self::flat(hand).curled(CurlPreset::Relaxed.amount());
+        let meet = relaxed
+            .joint(PhysicsHandBone::ThumbTip)
+            .position
+            .lerp(relaxed.joint(PhysicsHandBone::IndexTip).position, 0.5);
+
+        //anywhere from straight to a fist
+        let curl_range = (
+            -CurlPreset::Relaxed.amount(),
+            CurlPreset::Fist.amount() - CurlPreset::Relaxed.amount(),
+        );
+        //the thumb swings from its metacarpal, the index from its knuckle
+        let joints = relaxed.reached_for(Finger::Thumb, 0, meet, curl_range);
+        let thumb_tip = joints.joint(PhysicsHandBone::ThumbTip).position;
+        joints.reached_for(Finger::Index, 1, thumb_tip, curl_range)
+    }
+
+    pub fn centroid(&self) -> Vec3 {
+        let sum: Vec3 = self.inner.iter().map(|joint| joint.position).sum();
+        sum / self.inner.len() as f32
+    }
+
+    pub fn transformed(&self, origin: &HandOrigin) -> HandJoints {
+        HandJoints {
+            inner: self.inner.map(|joint| origin.apply(&joint)),
+        }
+    }
+}
+
+impl HandJoints {
+    pub fn joint(&self, bone: PhysicsHandBone) -> &HandJoint {
+        &self.inner[bone.index()]
+    }
+
+    // straight line distance from the wrist to the fingertip
+    pub fn finger_reach(&self, finger: Finger) -> f32 {
+        self.joint(PhysicsHandBone::Wrist)
+            .position
+            .distance(self.joint(finger.tip()).position)
+    }
+
+    // flexion at a joint in radians, the angle between the segment coming in from its parent and the
+    // one going out to its child. None for the wrist, the palm and the tips, which don't have both
+    pub fn joint_angle(&self, bone: PhysicsHandBone) -> Option<f32> {
+        let parent = BONE_CONNECTIONS
+            .iter()
+            .find(|(_, child)| *child == bone)
+            .map(|(parent, _)| *parent)?;
+
+        let mut children = BONE_CONNECTIONS
+            .iter()
+            .filter(|(parent, _)| *parent == bone)
+            .map(|(_, child)| *child);
+        let child = children.next()?;
+        if children.next().is_some() {
+            return None;
+        }
+
+        let position = self.joint(bone).position;
+        let incoming = position - self.joint(parent).position;
+        let outgoing = self.joint(child).position - position;
+        if incoming.length() <= f32::EPSILON || outgoing.length() <= f32::EPSILON {
+            return None;
+        }
+
+        Some(incoming.angle_between(outgoing))
+    }
+
+    // chord over polyline length of the proximal->tip chain, 1.0 is a perfectly straight finger
+    pub fn finger_straightness(&self, finger: Finger) -> f32 {
+        let bones = &finger.bones()[1..];
+        let mut length = 0.0;
+        for pair in bones.windows(2) {
+            length += self
+                .joint(pair[0])
+                .position
+                .distance(self.joint(pair[1]).position);
+        }
+
+        if length <= f32::EPSILON {
+            return 1.0;
+        }
+
+        let chord = self
+            .joint(bones[0])
+            .position
+            .distance(self.joint(bones[bones.len() - 1]).position);
+        chord / length
+    }
+
+    // summed segment lengths of every finger chain, a size measure independent of the pose
+    pub fn total_bone_length(&self) -> f32 {
+        let mut length = 0.0;
+        for finger in Finger::ALL.iter() {
+            for pair in finger.bones().windows(2) {
+                length += self
+                    .joint(pair[0])
+                    .position
+                    .distance(self.joint(pair[1]).position);
+            }
+        }
+        length
+    }
+
+    // uniformly scale the pose about the wrist
+    pub fn scaled(&self, scale: f32) -> HandJoints {
+        let wrist = self.joint(PhysicsHandBone::Wrist).position;
+        HandJoints {
+            inner: self.inner.map(|joint| HandJoint {
+                position: wrist + (joint.position - wrist) * scale,
+                radius: joint.radius * scale,
+                ..joint
+            }),
+        }
+    }
+
+    pub fn max_reach(&self) -> f32 {
+        Finger::ALL
+            .iter()
+            .map(|finger| self.finger_reach(*finger))
+            .fold(0.0, f32::max)
+    }
+
+    // signed distance from `point` to the surface of the finger, taken as one capsule per segment
+    // tapering between the joint radii. negative inside the finger
+    pub fn distance_to_finger(&self, finger: Finger, point: Vec3) -> f32 {
+        finger
+            .bones()
+            .windows(2)
+            .map(|pair| {
+                let start = self.joint(pair[0]);
+                let end = self.joint(pair[1]);
+                let segment = end.position - start.position;
+                let t = if segment.length_squared() <= f32::EPSILON {
+                    0.0
+                } else {
+                    ((point - start.position).dot(segment) / segment.length_squared())
+                        .clamp(0.0, 1.0)
+                };
+
+                let closest = start.position + segment * t;
+                point.distance(closest) - (start.radius + (end.radius - start.radius) * t)
+            })
+            .fold(f32::INFINITY, f32::min)
+    }
+
+    // thumb tip to little tip, wide for an open hand and small for a fist
+    pub fn hand_span(&self) -> f32 {
+        self.joint(PhysicsHandBone::ThumbTip)
+            .position
+            .distance(self.joint(PhysicsHandBone::LittleTip).position)
+    }
+}
+
+impl HandJoints {
+    // oriented box around the palm and fingertips, x is the dominant principal axis.
+    // returns the box transform and its half extents
+    pub fn grip_obb(&self) -> Option<(Transform, Vec3)> {
+        let mut points = vec![self.joint(PhysicsHandBone::Palm).position];
+        points.extend(
+            Finger::ALL
+                .iter()
+                .map(|finger| self.joint(finger.tip()).position),
+        );
+
+        let (mean, axes) = principal_axes(&points)?;
+
+        let mut min = Vec3::splat(f32::MAX);
+        let mut max = Vec3::splat(f32::MIN);
+        for point in points.iter() {
+            let offset = *point - mean;
+            let local = Vec3::new(
+                offset.dot(axes[0]),
+                offset.dot(axes[1]),
+                offset.dot(axes[2]),
+            );
+            min = min.min(local);
+            max = max.max(local);
+        }
+
+        let local_center = (min + max) / 2.0;
+        let center =
+            mean + axes[0] * local_center.x + axes[1] * local_center.y + axes[2] * local_center.z;
+        let rotation = Quat::from_mat3(&Mat3::from_cols(axes[0], axes[1], axes[2]));
+
+        Some((
+            Transform {
+                translation: center,
+                rotation,
+                ..Default::default()
+            },
+            (max - min) / 2.0,
+        ))
+    }
+}
+
+// mean and orthonormal principal axes (largest variance first) of a point cloud
+fn principal_axes(points: &[Vec3]) -> Option<(Vec3, [Vec3; 3])> {
+    if points.is_empty() {
+        return None;
+    }
+
+    let mean = points.iter().copied().sum::<Vec3>() / points.len() as f32;
+
+    let mut covariance = Mat3::ZERO;
+    for point in points.iter() {
+        let offset = *point - mean;
+        covariance += Mat3::from_cols(offset * offset.x, offset * offset.y, offset * offset.z);
+    }
+    covariance *= 1.0 / points.len() as f32;
+
+    let first = dominant_eigenvector(&covariance, Vec3::ONE.normalize()).unwrap_or(Vec3::X);
+    let first_value = first.dot(covariance * first);
+
+    //deflate and look for the next one, forced orthogonal to the first
+    let deflated = covariance
+        - Mat3::from_cols(first * first.x, first * first.y, first * first.z) * first_value;
+    let second = dominant_eigenvector(&deflated, first.any_orthonormal_vector())
+        .map(|second| (second - first * second.dot(first)).normalize_or_zero())
+        .filter(|second| *second != Vec3::ZERO)
+        .unwrap_or_else(|| first.any_orthonormal_vector());
+
+    Some((mean, [first, second, first.cross(second)]))
+}
+
+fn dominant_eigenvector(matrix: &Mat3, start: Vec3) -> Option<Vec3> {
+    let mut vector = start;
+    for _ in 0..32 {
+        let next = (*matrix * vector).normalize_or_zero();
+        if next == Vec3::ZERO {
+            return None;
+        }
+        vector = next;
+    }
+    Some(vector)
+}
+
+pub fn finger_reach(hand: Hand, finger: Finger) -> f32 {
+    get_default_hand(&hand).finger_reach(finger)
+}
+
+pub fn max_reach(hand: Hand) -> f32 {
+    get_default_hand(&hand).max_reach()
+}
+
+pub fn hand_span(hand: Hand) -> f32 {
+    get_default_hand(&hand).hand_span()
+}
+
+// metacarpal to tip. both hands share the same chains, `hand` keeps it in line with the other per hand lookups
+pub fn finger_chain(_hand: Hand, finger: Finger) -> Vec<PhysicsHandBone> {
+    finger.bones().to_vec()
+}
+
+pub fn joint_radius(hand: Hand, bone: PhysicsHandBone) -> f32 {
+    get_default_hand(&hand).joint(bone).radius
+}
+
+// default radius grown or shrunk by the calibrated hand size
+pub fn calibrated_joint_radius(hand: Hand, bone: PhysicsHandBone, hand_scale: &HandScale) -> f32 {
+    joint_radius(hand, bone) * hand_scale.get(hand)
+}
+
+// snapshot the bevy_oxr hand entities into a HandJoints, None if any of them is missing. a hidden
+// joint entity is flagged as not tracked
+pub fn get_tracked_hand_joints<F: QueryFilter>(
+    hand_res: &HandResource,
+    hand_query: &Query<(&Transform, &HandBoneRadius, Option<&Visibility>), F>,
+) -> Option<HandJoints> {
+    let mut inner = Vec::with_capacity(26);
+
+    for index in 0..26 {
+        let bone = NameToHandJoint::get_physics_bone_from_index(index);
+        let (transform, radius, visibility) = hand_query
+            .get(get_hand_resource_entity(hand_res, bone))
+            .ok()?;
+        let tracked = visibility != Some(&Visibility::Hidden);
+
+        inner.push(HandJoint {
+            position: transform.translation,
+            position_valid: true,
+            position_tracked: tracked,
+            orientation: sanitize_orientation(transform.rotation)?,
+            orientation_valid: true,
+            orientation_tracked: tracked,
+            radius: radius.0,
+        });
+    }
+
+    Some(HandJoints {
+        inner: inner.try_into().ok()?,
+    })
+}
+
+// snap a hand's physics bones back onto the rest pose, mostly useful while developing
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ResetPose {
+    pub hand: Hand,
+}
+
+pub fn reset_pose(
+    mut reset_events: EventReader<ResetPose>,
+    hand_origin: Res<HandOrigin>,
+    placement: Res<HandPlacement>,
+    mut bone_query: Query<(
+        &mut Transform,
+        &mut Velocity,
+        &PhysicsHandBone,
+        &Hand,
+        Option<&mut BoneInitState>,
+    )>,
+) {
+    for event in reset_events.read() {
+        let rest_pose = rest_pose(event.hand, &hand_origin, &placement);
+
+        for (mut transform, mut velocity, bone, hand, init_state) in bone_query.iter_mut() {
+            if *hand != event.hand {
+                continue;
+            }
+
+            let joint = rest_pose.joint(*bone);
+            transform.translation = joint.position;
+            transform.rotation = joint.orientation;
+            *velocity = Velocity::zero();
+
+            //let the matching start over from the new pose instead of chasing the old one
+            if let Some(mut init_state) = init_state {
+                *init_state = BoneInitState::False;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn in_front_of_camera_centers_the_hands_along_forward() {
+        let camera =
+            Transform::from_xyz(1.0, 1.6, 2.0).looking_at(Vec3::new(-2.0, 1.0, -3.0), Vec3::Y);
+        let default_centroid = Vec3::new(0.1, 1.2, -0.3);
+        let origin = HandOrigin::in_front_of_camera(&camera, 0.35, default_centroid);
+
+        let placed = origin.0.transform_point(default_centroid);
+        let expected = camera.translation + *camera.forward() * 0.35;
+        assert!(placed.distance(expected) < 1e-5);
+        assert!(origin.0.rotation.angle_between(camera.rotation) < 1e-5);
+    }
+
+    #[test]
+    fn origin_override_places_the_hands_exactly() {
+        let origin = Transform::from_xyz(0.2, 1.1, -0.4).with_rotation(Quat::from_rotation_y(0.5));
+        let placement = HandPlacement {
+            origin_override: Some(origin),
+            curl: CurlPreset::Fist,
+            ..default()
+        };
+
+        //wherever the camera is
+        let camera = Transform::from_xyz(3.0, 0.0, 1.0).looking_at(Vec3::ZERO, Vec3::Y);
+        let hand_origin = placement.origin(&camera, Vec3::new(0.1, 1.2, -0.3));
+        assert_eq!(hand_origin.0, origin);
+
+        //the palm isn't curled, it lands right where the origin puts the recorded one
+        let palm = *rest_pose(Hand::Right, &hand_origin, &placement).joint(PhysicsHandBone::Palm);
+        let recorded = *get_default_hand(&Hand::Right).joint(PhysicsHandBone::Palm);
+        assert_eq!(palm.position, origin.transform_point(recorded.position));
+        assert_eq!(palm.orientation, origin.rotation * recorded.orientation);
+    }
+
+    #[test]
+    fn grip_obb_contains_the_palm_and_fingertips() {
+        let joints = HandJoints::fist(Hand::Right);
+        let (transform, half_extents) = joints.grip_obb().unwrap();
+
+        let mut points = vec![joints.joint(PhysicsHandBone::Palm).position];
+        points.extend(
+            Finger::ALL
+                .iter()
+                .map(|finger| joints.joint(finger.tip()).position),
+        );
+        for point in points {
+            let local = transform.rotation.inverse() * (point - transform.translation);
+            assert!(local.abs().cmple(half_extents + 1e-4).all());
+        }
+        assert!(transform.rotation.is_normalized());
+    }
+
+    // the box's x axis, which has to be the one with the largest half extent
+    fn grip_long_axis(joints: &HandJoints) -> Vec3 {
+        let (transform, half_extents) = joints.grip_obb().unwrap();
+        assert_eq!(half_extents.max_element(), half_extents.x);
+        transform.rotation * Vec3::X
+    }
+
+    // from the palm toward the middle of the four fingertips
+    fn towards_fingertips(joints: &HandJoints) -> Vec3 {
+        let tips = Finger::ALL[1..]
+            .iter()
+            .map(|finger| joints.joint(finger.tip()).position)
+            .sum::<Vec3>()
+            / 4.0;
+        (tips - joints.joint(PhysicsHandBone::Palm).position).normalize()
+    }
+
+    #[test]
+    fn grip_obb_lies_along_the_largest_spread() {
+        //thumb tucked against the index, the points spread from the palm out to the fingertips
+        let mut tucked = HandJoints::flat(Hand::Right);
+        tucked.inner[PhysicsHandBone::ThumbTip.index()].position =
+            tucked.joint(PhysicsHandBone::IndexProximal).position;
+        let axis = grip_long_axis(&tucked);
+        assert!(axis.dot(towards_fingertips(&tucked)).abs() > 0.99);
+
+        //turned away from every world axis, an axis aligned box couldn't follow it
+        let turned = tucked.transformed(&HandOrigin(Transform::from_rotation(
+            Quat::from_rotation_z(0.8) * Quat::from_rotation_y(0.6),
+        )));
+        let axis = grip_long_axis(&turned);
+        assert!(axis.dot(towards_fingertips(&turned)).abs() > 0.99);
+        assert!(axis.abs().max_element() < 0.9);
+
+        //spread out, the thumb to index span is the larger spread and the box turns with it
+        let flat = HandJoints::flat(Hand::Right);
+        let span = (flat.joint(PhysicsHandBone::IndexTip).position
+            - flat.joint(PhysicsHandBone::ThumbTip).position)
+            .normalize();
+        assert!(grip_long_axis(&flat).dot(span).abs() > 0.99);
+    }
+
+    #[test]
+    fn max_reach_is_the_longest_finger() {
+        //the recorded hands have the middle finger longest
+        for hand in [Hand::Left, Hand::Right] {
+            let joints = get_default_hand(&hand);
+            assert_eq!(joints.max_reach(), joints.finger_reach(Finger::Middle));
+            assert_eq!(max_reach(hand), finger_reach(hand, Finger::Middle));
+        }
+
+        //stretch the ring finger past it and the ring finger sets the reach instead
+        let mut joints = get_default_hand(&Hand::Right);
+        let wrist = joints.joint(PhysicsHandBone::Wrist).position;
+        let tip = &mut joints.inner[PhysicsHandBone::RingTip.index()];
+        tip.position = wrist + (tip.position - wrist) * 1.2;
+        assert!(joints.finger_reach(Finger::Ring) > joints.finger_reach(Finger::Middle));
+        assert_eq!(joints.max_reach(), joints.finger_reach(Finger::Ring));
+
+        //curling pulls every fingertip in toward the wrist
+        let flat = HandJoints::flat(Hand::Right);
+        let fist = HandJoints::fist(Hand::Right);
+        for finger in Finger::ALL
+            .iter()
+            .filter(|finger| **finger != Finger::Thumb)
+        {
+            assert!(fist.finger_reach(*finger) < flat.finger_reach(*finger));
+        }
+    }
+
+    #[test]
+    fn index_chain_runs_metacarpal_to_tip() {
+        use PhysicsHandBone::*;
+        assert_eq!(
+            finger_chain(Hand::Left, Finger::Index),
+            vec![
+                IndexMetacarpal,
+                IndexProximal,
+                IndexIntermediate,
+                IndexDistal,
+                IndexTip
+            ]
+        );
+        assert_eq!(
+            finger_chain(Hand::Right, Finger::Thumb),
+            vec![ThumbMetacarpal, ThumbProximal, ThumbDistal, ThumbTip]
+        );
+    }
+
+    #[test]
+    fn knuckle_angle_is_the_bend_between_segments() {
+        // index finger up along y, bent by `bend` about x at the proximal joint
+        let finger = |bend: f32| {
+            let mut joints = get_default_hand(&Hand::Right);
+            let bent = Quat::from_rotation_x(bend) * Vec3::Y;
+            let positions = [
+                Vec3::ZERO,
+                Vec3::Y * 0.05,
+                Vec3::Y * 0.05 + bent * 0.04,
+                Vec3::Y * 0.05 + bent * 0.065,
+                Vec3::Y * 0.05 + bent * 0.085,
+            ];
+            for (bone, position) in Finger::Index.bones().iter().zip(positions) {
+                joints.inner[bone.index()].position = position;
+            }
+            joints
+        };
+
+        let straight = finger(0.0);
+        assert!(
+            straight
+                .joint_angle(PhysicsHandBone::IndexProximal)
+                .unwrap()
+                < 1e-4
+        );
+
+        let bent = finger(0.6);
+        let angle = bent.joint_angle(PhysicsHandBone::IndexProximal).unwrap();
+        assert!((angle - 0.6).abs() < 1e-4);
+        //the rest of the finger is still straight
+        assert!(bent.joint_angle(PhysicsHandBone::IndexDistal).unwrap() < 1e-4);
+
+        assert_eq!(bent.joint_angle(PhysicsHandBone::IndexTip), None);
+        assert_eq!(bent.joint_angle(PhysicsHandBone::Wrist), None);
+    }
+
+    #[test]
+    fn joint_radius_reads_the_default_table() {
+        use crate::constants::get_default_right_hand;
+
+        let palm = joint_radius(Hand::Right, PhysicsHandBone::Palm);
+        assert_eq!(
+            palm,
+            get_default_right_hand().inner[PhysicsHandBone::Palm.index()].radius
+        );
+
+        let scale = HandScale {
+            left: 1.0,
+            right: 1.2,
+        };
+        assert!(
+            (calibrated_joint_radius(Hand::Right, PhysicsHandBone::Palm, &scale) - palm * 1.2)
+                .abs()
+                < 1e-6
+        );
+    }
+
+    #[test]
+    fn orientations_are_normalized_or_rejected() {
+        let stretched = sanitize_orientation(Quat::from_xyzw(0.0, 0.0, 0.0, 1.3)).unwrap();
+        assert!((stretched.length() - 1.0).abs() < 1e-6);
+        assert_eq!(
+            sanitize_orientation(Quat::from_xyzw(f32::NAN, 0.0, 0.0, 1.0)),
+            None
+        );
+        assert_eq!(
+            sanitize_orientation(Quat::from_xyzw(0.0, 0.0, 0.0, 0.0)),
+            None
+        );
+
+        //one bad joint drops the whole frame
+        let mut joints = get_default_hand(&Hand::Right);
+        joints.inner[PhysicsHandBone::IndexTip.index()].orientation =
+            Quat::from_xyzw(f32::NAN, 0.0, 0.0, 1.0);
+        assert!(joints.sanitized().is_none());
+    }
+
+    #[test]
+    fn reset_pose_snaps_one_hand_back_to_rest() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = World::new();
+        world.init_resource::<HandOrigin>();
+        world.init_resource::<HandPlacement>();
+        world.init_resource::<Events<ResetPose>>();
+
+        let mut bone = |hand: Hand| {
+            world
+                .spawn((
+                    Transform::from_xyz(1.0, 2.0, 3.0),
+                    Velocity::linear(Vec3::X),
+                    PhysicsHandBone::IndexTip,
+                    hand,
+                    BoneInitState::True,
+                ))
+                .id()
+        };
+        let right = bone(Hand::Right);
+        let left = bone(Hand::Left);
+
+        world.send_event(ResetPose { hand: Hand::Right });
+        world.run_system_once(reset_pose);
+
+        let rest = rest_pose(
+            Hand::Right,
+            &HandOrigin::default(),
+            &HandPlacement::default(),
+        );
+        let joint = rest.joint(PhysicsHandBone::IndexTip);
+        let transform = world.get::<Transform>(right).unwrap();
+        assert_eq!(transform.translation, joint.position);
+        assert_eq!(transform.rotation, joint.orientation);
+        assert_eq!(world.get::<Velocity>(right).unwrap().linvel, Vec3::ZERO);
+        assert!(world.get::<BoneInitState>(right) == Some(&BoneInitState::False));
+
+        assert_eq!(world.get::<Velocity>(left).unwrap().linvel, Vec3::X);
+    }
+
+    #[test]
+    fn test_hands_have_the_expected_curl() {
+        for hand in [Hand::Left, Hand::Right] {
+            let flat = HandJoints::flat(hand);
+            let fist = HandJoints::fist(hand);
+            for finger in Finger::ALL.iter() {
+                assert!(flat.finger_straightness(*finger) > 0.99);
+            }
+            for finger in Finger::ALL
+                .iter()
+                .filter(|finger| **finger != Finger::Thumb)
+            {
+                assert!(fist.finger_straightness(*finger) < 0.6);
+            }
+
+            //pinching brings the thumb and index tips together
+            let pinch = HandJoints::pinch(hand);
+            let gap = pinch
+                .joint(PhysicsHandBone::ThumbTip)
+                .position
+                .distance(pinch.joint(PhysicsHandBone::IndexTip).position);
+            assert!(gap < 1e-5);
+
+            //by bending the fingers, not by stretching their last bones
+            assert!((pinch.total_bone_length() - flat.total_bone_length()).abs() < 1e-5);
+            for finger in [Finger::Thumb, Finger::Index] {
+                for pair in finger.bones().windows(2) {
+                    let length = |joints: &HandJoints| {
+                        joints
+                            .joint(pair[0])
+                            .position
+                            .distance(joints.joint(pair[1]).position)
+                    };
+                    assert!((length(&pinch) - length(&flat)).abs() < 1e-5);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn an_open_hand_spans_more_than_a_fist() {
+        let open = HandJoints::flat(Hand::Right).hand_span();
+        let fist = HandJoints::fist(Hand::Right).hand_span();
+        assert!(open > 0.1);
+        assert!(fist < open);
+
+        let default = get_default_hand(&Hand::Left);
+        let expected = default
+            .joint(PhysicsHandBone::ThumbTip)
+            .position
+            .distance(default.joint(PhysicsHandBone::LittleTip).position);
+        assert_eq!(hand_span(Hand::Left), expected);
+    }
+
+    #[test]
+    fn finger_distance_is_negative_inside_the_capsules() {
+        //a straight index finger 1cm thick all along
+        let mut joints = HandJoints::flat(Hand::Right);
+        for bone in Finger::Index.bones().iter() {
+            joints.inner[bone.index()].radius = 0.01;
+        }
+        let start = joints.joint(PhysicsHandBone::IndexProximal).position;
+        let end = joints.joint(PhysicsHandBone::IndexIntermediate).position;
+        let middle = start.lerp(end, 0.5);
+        let side = (end - start).any_orthonormal_vector();
+
+        let distance = |point| joints.distance_to_finger(Finger::Index, point);
+        assert!((distance(middle) + 0.01).abs() < 1e-5);
+        assert!((distance(middle + side * 0.004) + 0.006).abs() < 1e-5);
+        assert!((distance(middle + side * 0.03) - 0.02).abs() < 1e-5);
+
+        //past the tip it is the distance to the tip sphere
+        let tip = joints.joint(PhysicsHandBone::IndexTip).position;
+        let along = (tip - start).normalize();
+        assert!((distance(tip + along * 0.05) - 0.04).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,241 @@
+use bevy::prelude::*;
+use bevy_oxr::xr_input::{
+    hands::{
+        common::{HandBoneRadius, HandsResource},
+        HandBone,
+    },
+    Hand,
+};
+
+use crate::config::{HandPhysicsConfig, TrackingLossBehavior};
+use crate::constants::{
+    get_default_hand, get_hand_resource_entity, HandJoints, NameToHandJoint, PhysicsHandBone,
+};
+use crate::targets::TargetHistory;
+use crate::tracking::TrackedHands;
+
+// where update_physics_hands gets the joints it drives the bones towards, so replay, procedural
+// or networked hands can stand in for bevy_oxr. None for a hand leaves its bones alone this step
+pub trait HandTargetProvider: Send + Sync + 'static {
+    fn targets(&self, hand: Hand) -> Option<HandJoints>;
+}
+
+// insert to drive the hands from something other than bevy_oxr, without it OxrHandTargets is used
+#[derive(Resource)]
+pub struct HandTargetSource(pub Box<dyn HandTargetProvider>);
+
+impl HandTargetSource {
+    pub fn new(provider: impl HandTargetProvider) -> Self {
+        Self(Box::new(provider))
+    }
+}
+
+pub type OxrJointQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Transform,
+        Option<&'static HandBoneRadius>,
+        Option<&'static TargetHistory>,
+    ),
+    (With<HandBone>, Without<PhysicsHandBone>),
+>;
+
+// the bevy_oxr joint entities sampled for one fixed step, with target interpolation and
+// latency compensation applied
+#[derive(Debug, Clone, Default)]
+pub struct OxrHandTargets {
+    pub left: Option<HandJoints>,
+    pub right: Option<HandJoints>,
+}
+
+impl OxrHandTargets {
+    pub fn sample(
+        hands_res: &HandsResource,
+        joint_query: &OxrJointQuery,
+        tracked_hands: &TrackedHands,
+        config: &HandPhysicsConfig,
+        now: f64,
+    ) -> Self {
+        Self {
+            left: sample_hand(
+                Hand::Left,
+                hands_res,
+                joint_query,
+                tracked_hands,
+                config,
+                now,
+            ),
+            right: sample_hand(
+                Hand::Right,
+                hands_res,
+                joint_query,
+                tracked_hands,
+                config,
+                now,
+            ),
+        }
+    }
+}
+
+impl HandTargetProvider for OxrHandTargets {
+    fn targets(&self, hand: Hand) -> Option<HandJoints> {
+        match hand {
+            Hand::Left => self.left,
+            Hand::Right => self.right,
+        }
+    }
+}
+
+// None as soon as one joint entity is missing, e.g. while the app is tearing down
+fn sample_hand(
+    hand: Hand,
+    hands_res: &HandsResource,
+    joint_query: &OxrJointQuery,
+    tracked_hands: &TrackedHands,
+    config: &HandPhysicsConfig,
+    now: f64,
+) -> Option<HandJoints> {
+    //hold_pose_on_tracking_loss has put the fallback pose in TrackedHands while the hand is lost,
+    //aim at that instead of wherever the lost joint entities were left
+    if config.tracking_loss != TrackingLossBehavior::FollowSource {
+        if let Some(fallback) = tracked_hands
+            .get(hand)
+            .filter(|joints| !joints.is_fully_tracked())
+        {
+            return Some(*fallback);
+        }
+    }
+
+    let hand_res = match hand {
+        Hand::Left => hands_res.left,
+        Hand::Right => hands_res.right,
+    };
+    let mut joints = get_default_hand(&hand);
+
+    for (index, joint) in joints.inner.iter_mut().enumerate() {
+        let bone = NameToHandJoint::get_physics_bone_from_index(index);
+        let (transform, radius, history) = joint_query
+            .get(get_hand_resource_entity(&hand_res, bone))
+            .ok()?;
+
+        //tracking only updates once per frame, aim the fixed steps in between
+        //at an interpolated target instead of holding the latest one
+        let mut target = match history {
+            Some(history) if config.interpolate_targets => history.sample(now),
+            _ => *transform,
+        };
+
+        //extrapolate along the tracked velocity to make up for latency
+        if let Some(history) = history {
+            target.translation += history.velocity() * config.latency_compensation.as_secs_f32();
+        }
+
+        joint.position = target.translation;
+        joint.orientation = target.rotation;
+        if let Some(radius) = radius {
+            joint.radius = radius.0;
+        }
+    }
+
+    Some(joints)
+}
+
+// the joint a bone's segment runs to, None for the tips, the palm and the wrist
+pub fn segment_end(bone: PhysicsHandBone) -> Option<PhysicsHandBone> {
+    let (finger, _) = bone.decompose()?;
+    let bones = finger.bones();
+    let index = bones.iter().position(|other| *other == bone)?;
+    bones.get(index + 1).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::pose::{rest_pose, HandOrigin, HandPlacement};
+    use crate::test_utils::{bone_transform, hand_app, spawn_joint_entities, step, FixedTargets};
+
+    // the right index tip target, sampled with the given latency compensation
+    fn index_tip_target(latency_compensation: Duration) -> Vec3 {
+        let mut world = World::new();
+        spawn_joint_entities(
+            &mut world,
+            &get_default_hand(&Hand::Left),
+            &get_default_hand(&Hand::Right),
+        );
+        world.init_resource::<TrackedHands>();
+        world.insert_resource(HandPhysicsConfig {
+            latency_compensation,
+            interpolate_targets: false,
+            ..default()
+        });
+
+        //moving at a constant 1 m/s along x, the last frame ended at x = 0.1
+        let tip = get_hand_resource_entity(
+            &world.resource::<HandsResource>().right,
+            PhysicsHandBone::IndexTip,
+        );
+        let mut history = TargetHistory::new(Transform::IDENTITY, 0.0);
+        history.push(Transform::from_xyz(0.1, 0.0, 0.0), 0.1);
+        world
+            .entity_mut(tip)
+            .insert((Transform::from_xyz(0.1, 0.0, 0.0), history));
+
+        let targets = world.run_system_once(
+            |hands_res: Res<HandsResource>,
+             joint_query: OxrJointQuery,
+             tracked_hands: Res<TrackedHands>,
+             config: Res<HandPhysicsConfig>| {
+                OxrHandTargets::sample(&hands_res, &joint_query, &tracked_hands, &config, 0.1)
+            },
+        );
+        targets
+            .right
+            .unwrap()
+            .joint(PhysicsHandBone::IndexTip)
+            .position
+    }
+
+    #[test]
+    fn latency_compensation_leads_by_velocity_times_latency() {
+        assert_eq!(index_tip_target(Duration::ZERO), Vec3::new(0.1, 0.0, 0.0));
+
+        let led = index_tip_target(Duration::from_millis(50));
+        assert!(led.distance(Vec3::new(0.15, 0.0, 0.0)) < 1e-5);
+    }
+
+    #[test]
+    fn both_hands_follow_a_mock_provider() {
+        let mut app = hand_app(HandPhysicsConfig::default());
+        let origin = *app.world.resource::<HandOrigin>();
+        let placement = *app.world.resource::<HandPlacement>();
+        let moved = |hand, offset| {
+            rest_pose(hand, &origin, &placement)
+                .transformed(&HandOrigin(Transform::from_translation(offset)))
+        };
+        let left = moved(Hand::Left, Vec3::new(0.0, 0.1, 0.0));
+        let right = moved(Hand::Right, Vec3::new(0.05, -0.1, 0.0));
+        app.insert_resource(HandTargetSource::new(FixedTargets { left, right }));
+        step(&mut app, 30);
+
+        for (hand, joints) in [(Hand::Left, left), (Hand::Right, right)] {
+            for bone in [
+                PhysicsHandBone::ThumbMetacarpal,
+                PhysicsHandBone::IndexProximal,
+                PhysicsHandBone::LittleDistal,
+            ] {
+                let position = bone_transform(&app, hand, bone).translation;
+                assert!(
+                    position.distance(joints.joint(bone).position) < 0.01,
+                    "{:?} {:?}",
+                    hand,
+                    bone
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,431 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_oxr::xr_input::Hand;
+use bevy_rapier3d::prelude::*;
+
+use crate::constants::{get_default_hand, Finger, HandJoints, PhysicsHandBone};
+use crate::handedness::{DominantHand, HandRole};
+use crate::tips::TipBone;
+use crate::tracking::TrackedHands;
+
+#[derive(SystemParam)]
+pub struct PhysicsHands<'w, 's> {
+    rapier_context: Res<'w, RapierContext>,
+    dominant_hand: Res<'w, DominantHand>,
+    bone_query: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static PhysicsHandBone,
+            &'static Hand,
+            &'static Transform,
+        ),
+    >,
+    tip_query: Query<'w, 's, (Entity, &'static PhysicsHandBone, &'static Hand), With<TipBone>>,
+    tracked_hands: Res<'w, TrackedHands>,
+}
+
+impl<'w, 's> PhysicsHands<'w, 's> {
+    pub fn hand(&self, role: HandRole) -> Hand {
+        self.dominant_hand.resolve(role)
+    }
+
+    pub fn bone_entity(&self, hand: Hand, bone: PhysicsHandBone) -> Option<(Entity, Transform)> {
+        self.bone_query
+            .iter()
+            .find(|(_, other_bone, other_hand, _)| **other_bone == bone && **other_hand == hand)
+            .map(|(entity, _, _, transform)| (entity, *transform))
+    }
+
+    // nearest bone origin to a world point, e.g. a contact point, with its distance
+    pub fn closest_bone(&self, hand: Hand, point: Vec3) -> Option<(PhysicsHandBone, f32)> {
+        self.bone_query
+            .iter()
+            .filter(|(_, _, bone_hand, _)| **bone_hand == hand)
+            .map(|(_, bone, _, transform)| (*bone, transform.translation.distance(point)))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+    }
+
+    // reverse lookup for entities coming out of collision events
+    pub fn identify_bone(&self, entity: Entity) -> Option<(Hand, PhysicsHandBone)> {
+        self.bone_query
+            .get(entity)
+            .ok()
+            .map(|(_, bone, hand, _)| (*hand, *bone))
+    }
+
+    // every (bone, other entity) pair with at least one active contact point this step
+    pub fn contacts(&self, hand: Hand) -> Vec<(PhysicsHandBone, Entity)> {
+        let mut contacts = Vec::new();
+
+        for (entity, bone, bone_hand, _) in self.bone_query.iter() {
+            if *bone_hand != hand {
+                continue;
+            }
+
+            for contact_pair in self.rapier_context.contacts_with(entity) {
+                if !contact_pair.has_any_active_contacts() {
+                    continue;
+                }
+
+                let other = if contact_pair.collider1() == entity {
+                    contact_pair.collider2()
+                } else {
+                    contact_pair.collider1()
+                };
+
+                contacts.push((*bone, other));
+            }
+        }
+
+        contacts
+    }
+
+    // current pose of the physics bones, joints without a spawned bone keep the default data
+    pub fn pose(&self, hand: Hand) -> HandJoints {
+        let mut pose = get_default_hand(&hand);
+
+        for (_, bone, bone_hand, transform) in self.bone_query.iter() {
+            if *bone_hand != hand {
+                continue;
+            }
+
+            let joint = &mut pose.inner[bone.index()];
+            joint.position = transform.translation;
+            joint.orientation = transform.rotation;
+        }
+
+        pose
+    }
+
+    pub fn grip_obb(&self, hand: Hand) -> Option<(Transform, Vec3)> {
+        self.pose(hand).grip_obb()
+    }
+
+    // thumb to little, None until all five tips have been spawned
+    pub fn fingertips(&self, hand: Hand) -> Option<[Entity; 5]> {
+        let mut tips = [None; 5];
+
+        for (entity, bone, bone_hand) in self.tip_query.iter() {
+            if *bone_hand != hand {
+                continue;
+            }
+
+            if let Some((finger, _)) = bone.decompose() {
+                tips[finger as usize] = Some(entity);
+            }
+        }
+
+        let [thumb, index, middle, ring, little] = tips;
+        Some([thumb?, index?, middle?, ring?, little?])
+    }
+
+    // palm to palm distance under `threshold`, false until both palms exist
+    pub fn hands_touching(&self, threshold: f32) -> bool {
+        let left = self.bone_entity(Hand::Left, PhysicsHandBone::Palm);
+        let right = self.bone_entity(Hand::Right, PhysicsHandBone::Palm);
+
+        match (left, right) {
+            (Some((_, left)), Some((_, right))) => {
+                left.translation.distance(right.translation) < threshold
+            }
+            _ => false,
+        }
+    }
+
+    // world point at normalized arc length `t` along the finger, 0 the metacarpal and 1 the tip
+    pub fn sample_finger(&self, hand: Hand, finger: Finger, t: f32) -> Option<Vec3> {
+        let points = finger
+            .bones()
+            .iter()
+            .map(|bone| {
+                self.bone_entity(hand, *bone)
+                    .map(|(_, transform)| transform.translation)
+            })
+            .collect::<Option<Vec<Vec3>>>()?;
+
+        let total: f32 = points
+            .windows(2)
+            .map(|pair| pair[0].distance(pair[1]))
+            .sum();
+        let mut remaining = t.clamp(0.0, 1.0) * total;
+
+        for pair in points.windows(2) {
+            let length = pair[0].distance(pair[1]);
+            if remaining <= length {
+                if length <= f32::EPSILON {
+                    return Some(pair[0]);
+                }
+                return Some(pair[0].lerp(pair[1], remaining / length));
+            }
+            remaining -= length;
+        }
+
+        points.last().copied()
+    }
+
+    // tracked palm inside the camera frustum, tracking tends to drop outside of it so losing a hand
+    // that was last seen out of view is usually nothing to worry about. the palm bone isn't driven,
+    // so this goes by the tracked joint. false while the palm isn't tracked
+    pub fn hand_in_fov(
+        &self,
+        hand: Hand,
+        camera: &Camera,
+        camera_transform: &GlobalTransform,
+    ) -> bool {
+        self.hand_in_view(hand, camera.projection_matrix(), camera_transform)
+    }
+
+    // hand_in_fov for a projection matrix, see point_in_view
+    pub fn hand_in_view(
+        &self,
+        hand: Hand,
+        projection: Mat4,
+        camera_transform: &GlobalTransform,
+    ) -> bool {
+        self.tracked_hands
+            .get(hand)
+            .map(|joints| *joints.joint(PhysicsHandBone::Palm))
+            .filter(|palm| palm.position_tracked)
+            .is_some_and(|palm| point_in_view(palm.position, projection, camera_transform))
+    }
+
+    // where to put an object of `object_size` (full extents, in its own frame) so it rests in the
+    // palm: turned with the wrist and pushed out of the palm (-Y in openxr joint space) by half its
+    // height so it sits on the skin rather than inside the hand
+    pub fn palm_grip_transform(&self, hand: Hand, object_size: Vec3) -> Option<Transform> {
+        let (_, palm) = self.bone_entity(hand, PhysicsHandBone::Palm)?;
+        let rotation = self
+            .bone_entity(hand, PhysicsHandBone::Wrist)
+            .map_or(palm.rotation, |(_, wrist)| wrist.rotation);
+
+        Some(Transform {
+            translation: palm.translation + rotation * Vec3::NEG_Y * (object_size.y * 0.5),
+            rotation,
+            ..Default::default()
+        })
+    }
+
+    // signed distance to the finger as the bones are posed right now, with the default joint radii
+    pub fn distance_to_finger(&self, hand: Hand, finger: Finger, point: Vec3) -> f32 {
+        self.pose(hand).distance_to_finger(finger, point)
+    }
+
+    // span of the hand as the bones are posed right now
+    pub fn hand_span(&self, hand: Hand) -> f32 {
+        self.pose(hand).hand_span()
+    }
+
+    pub fn joint_angle(&self, hand: Hand, bone: PhysicsHandBone) -> Option<f32> {
+        self.pose(hand).joint_angle(bone)
+    }
+
+    pub fn contacts_for(&self, role: HandRole) -> Vec<(PhysicsHandBone, Entity)> {
+        self.contacts(self.hand(role))
+    }
+
+    pub fn pose_for(&self, role: HandRole) -> HandJoints {
+        self.pose(self.hand(role))
+    }
+}
+
+pub fn point_in_fov(point: Vec3, camera: &Camera, camera_transform: &GlobalTransform) -> bool {
+    point_in_view(point, camera.projection_matrix(), camera_transform)
+}
+
+// inside the view frustum when it lands within the ndc cube, bevy's reverse z puts the near plane
+// at 1 and the far plane at 0
+pub fn point_in_view(point: Vec3, projection: Mat4, camera_transform: &GlobalTransform) -> bool {
+    let world_to_ndc = projection * camera_transform.compute_matrix().inverse();
+    let ndc = world_to_ndc.project_point3(point);
+    !ndc.is_nan() && ndc.x.abs() <= 1.0 && ndc.y.abs() <= 1.0 && ndc.z >= 0.0 && ndc.z <= 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::config::HandPhysicsConfig;
+    use crate::test_utils::{bone_entity, bone_transform, hand_app, spawn_ball, step};
+
+    #[test]
+    fn contacts_lists_every_touching_bone() {
+        let mut app = hand_app(HandPhysicsConfig::default());
+
+        //on the knuckle, where the proximal capsule ends and the intermediate one starts
+        let knuckle =
+            bone_transform(&app, Hand::Right, PhysicsHandBone::IndexIntermediate).translation;
+        let ball = spawn_ball(&mut app, knuckle, 0.01);
+        step(&mut app, 2);
+
+        let contacts = app
+            .world
+            .run_system_once(|hands: PhysicsHands| hands.contacts(Hand::Right));
+        assert!(contacts.contains(&(PhysicsHandBone::IndexProximal, ball)));
+        assert!(contacts.contains(&(PhysicsHandBone::IndexIntermediate, ball)));
+        assert!(app
+            .world
+            .run_system_once(|hands: PhysicsHands| hands.contacts(Hand::Left))
+            .is_empty());
+    }
+
+    #[test]
+    fn closest_bone_finds_the_index_tip() {
+        let mut app = hand_app(HandPhysicsConfig::default());
+        let tip = bone_transform(&app, Hand::Right, PhysicsHandBone::IndexTip).translation;
+        let point = tip + Vec3::new(0.002, 0.001, 0.0);
+
+        let closest = app
+            .world
+            .run_system_once(move |hands: PhysicsHands| hands.closest_bone(Hand::Right, point))
+            .unwrap();
+        assert_eq!(closest.0, PhysicsHandBone::IndexTip);
+        assert!((closest.1 - point.distance(tip)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn sample_finger_runs_metacarpal_to_tip() {
+        let mut app = hand_app(HandPhysicsConfig::default());
+        let points: Vec<Vec3> = Finger::Index
+            .bones()
+            .iter()
+            .map(|bone| bone_transform(&app, Hand::Right, *bone).translation)
+            .collect();
+        let total: f32 = points
+            .windows(2)
+            .map(|pair| pair[0].distance(pair[1]))
+            .sum();
+
+        let [start, middle, end] = app.world.run_system_once(|hands: PhysicsHands| {
+            [0.0, 0.5, 1.0].map(|t| hands.sample_finger(Hand::Right, Finger::Index, t).unwrap())
+        });
+        assert!(start.distance(points[0]) < 1e-6);
+        assert!(end.distance(*points.last().unwrap()) < 1e-6);
+        //half the finger's length along the bones from either end, so no further than that in
+        //a straight line
+        assert!(middle.distance(start) <= total / 2.0 + 1e-5);
+        assert!(middle.distance(end) <= total / 2.0 + 1e-5);
+        assert!(middle.distance(start) > total / 4.0);
+    }
+
+    #[test]
+    fn identify_bone_maps_entities_back_to_hand_and_bone() {
+        let mut app = hand_app(HandPhysicsConfig::default());
+        let bone = bone_entity(&app, Hand::Left, PhysicsHandBone::RingDistal);
+        let other = app.world.spawn_empty().id();
+
+        let identified = app.world.run_system_once(move |hands: PhysicsHands| {
+            (hands.identify_bone(bone), hands.identify_bone(other))
+        });
+        assert_eq!(
+            identified,
+            (Some((Hand::Left, PhysicsHandBone::RingDistal)), None)
+        );
+    }
+
+    #[test]
+    fn fingertips_run_thumb_to_little() {
+        let mut app = hand_app(HandPhysicsConfig::default());
+        let tips = app
+            .world
+            .run_system_once(|hands: PhysicsHands| hands.fingertips(Hand::Right))
+            .unwrap();
+
+        let expected = Finger::ALL.map(|finger| bone_entity(&app, Hand::Right, finger.tip()));
+        assert_eq!(tips, expected);
+    }
+
+    #[test]
+    fn points_in_front_of_the_camera_are_in_view() {
+        use bevy::render::camera::CameraProjection;
+
+        let projection = PerspectiveProjection::default().get_projection_matrix();
+        //at head height looking down -z
+        let camera = GlobalTransform::from_xyz(0.0, 1.6, 0.0);
+        let in_view = |point: Vec3| point_in_view(point, projection, &camera);
+
+        assert!(in_view(Vec3::new(0.1, 1.5, -0.5)));
+        //off to the side, and behind
+        assert!(!in_view(Vec3::new(3.0, 1.6, -0.5)));
+        assert!(!in_view(Vec3::new(0.0, 1.6, 0.5)));
+    }
+
+    #[test]
+    fn grip_transform_rests_on_the_palm() {
+        let mut app = hand_app(HandPhysicsConfig::default());
+        let rotation = Quat::from_rotation_x(0.4) * Quat::from_rotation_y(-0.3);
+        let palm = Vec3::new(0.2, 1.1, -0.3);
+        for (bone, translation) in [
+            (PhysicsHandBone::Palm, palm),
+            (PhysicsHandBone::Wrist, palm + rotation * Vec3::Z * 0.05),
+        ] {
+            let entity = bone_entity(&app, Hand::Right, bone);
+            *app.world.get_mut::<Transform>(entity).unwrap() =
+                Transform::from_translation(translation).with_rotation(rotation);
+        }
+
+        let grip = app
+            .world
+            .run_system_once(|hands: PhysicsHands| {
+                hands.palm_grip_transform(Hand::Right, Vec3::new(0.06, 0.04, 0.1))
+            })
+            .unwrap();
+        assert!(grip.rotation.angle_between(rotation) < 1e-5);
+        //half its height out of the palm, along the palm's -Y
+        let expected = palm + rotation * Vec3::NEG_Y * 0.02;
+        assert!(grip.translation.distance(expected) < 1e-5);
+    }
+
+    #[test]
+    fn hand_in_fov_follows_the_tracked_palm() {
+        use bevy::render::camera::CameraProjection;
+
+        use crate::pose::{rest_pose, HandOrigin, HandPlacement};
+        use crate::test_utils::drive;
+
+        let mut app = hand_app(HandPhysicsConfig::default());
+        let origin = *app.world.resource::<HandOrigin>();
+        let placement = *app.world.resource::<HandPlacement>();
+        let right = rest_pose(Hand::Right, &origin, &placement);
+        drive(&mut app, Hand::Right, right);
+        step(&mut app, 1);
+        let palm = right.joint(PhysicsHandBone::Palm).position;
+        //the left hand was never tracked, wherever its bones are
+        let left_palm = bone_transform(&app, Hand::Left, PhysicsHandBone::Palm).translation;
+
+        //a camera half a metre behind `palm`, looking at it or turned away from it
+        let mut spawn_camera = |palm: Vec3, facing: bool| {
+            let eye = palm + Vec3::new(0.0, 0.1, 0.5);
+            let target = if facing { palm } else { eye * 2.0 - palm };
+            app.world
+                .spawn((
+                    Camera::default(),
+                    PerspectiveProjection::default(),
+                    GlobalTransform::from(
+                        Transform::from_translation(eye).looking_at(target, Vec3::Y),
+                    ),
+                ))
+                .id()
+        };
+        let facing = spawn_camera(palm, true);
+        let away = spawn_camera(palm, false);
+        let facing_left = spawn_camera(left_palm, true);
+
+        let mut in_view = |hand: Hand, camera: Entity| {
+            app.world.run_system_once_with(
+                (hand, camera),
+                |In((hand, camera)): In<(Hand, Entity)>,
+                 hands: PhysicsHands,
+                 cameras: Query<(&PerspectiveProjection, &GlobalTransform), With<Camera>>| {
+                    let (projection, camera_transform) = cameras.get(camera).unwrap();
+                    hands.hand_in_view(hand, projection.get_projection_matrix(), camera_transform)
+                },
+            )
+        };
+
+        assert!(in_view(Hand::Right, facing));
+        assert!(!in_view(Hand::Right, away));
+        assert!(!in_view(Hand::Left, facing_left));
+    }
+}
@@ -0,0 +1,161 @@
+use bevy::{prelude::*, utils::HashSet};
+use bevy_oxr::xr_input::Hand;
+use bevy_rapier3d::prelude::*;
+
+use crate::config::HandPhysicsConfig;
+use crate::constants::PhysicsHandBone;
+
+// a bone started touching `other`
+#[derive(Event, Debug, Clone, Copy)]
+pub struct BoneContact {
+    pub bone_entity: Entity,
+    pub hand: Hand,
+    pub bone: PhysicsHandBone,
+    pub other: Entity,
+}
+
+pub type ContactReactionFn = fn(&mut Commands, BoneContact);
+
+// bevy 0.13 has no observers, so per bone reactions hang off the bone entity instead.
+// insert one on a bone (e.g. from the OnBoneSpawn hook) and it runs only for that bone's contacts,
+// every contact is also sent as a BoneContact event for anyone who'd rather read them all
+#[derive(Component, Clone, Copy)]
+pub struct ContactReaction(pub ContactReactionFn);
+
+pub fn trigger_bone_contacts(
+    mut commands: Commands,
+    config: Res<HandPhysicsConfig>,
+    rapier_context: Res<RapierContext>,
+    bone_query: Query<(Entity, &PhysicsHandBone, &Hand, Option<&ContactReaction>)>,
+    mut touching: Local<HashSet<(Entity, Entity)>>,
+    mut contact_events: EventWriter<BoneContact>,
+) {
+    let mut now_touching = HashSet::new();
+
+    for (entity, bone, hand, reaction) in bone_query.iter() {
+        for contact_pair in rapier_context.contacts_with(entity) {
+            if !contact_pair.has_any_active_contacts() {
+                continue;
+            }
+
+            let other = if contact_pair.collider1() == entity {
+                contact_pair.collider2()
+            } else {
+                contact_pair.collider1()
+            };
+
+            if config.ignore_self_contacts
+                && bone_query
+                    .get(other)
+                    .is_ok_and(|(_, _, other_hand, _)| other_hand == hand)
+            {
+                continue;
+            }
+            now_touching.insert((entity, other));
+
+            //only the step the contact starts
+            if touching.contains(&(entity, other)) {
+                continue;
+            }
+
+            let contact = BoneContact {
+                bone_entity: entity,
+                hand: *hand,
+                bone: *bone,
+                other,
+            };
+            if let Some(reaction) = reaction {
+                (reaction.0)(&mut commands, contact);
+            }
+            contact_events.send(contact);
+        }
+    }
+
+    *touching = now_touching;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{bone_entity, bone_transform, events, hand_app, spawn_ball, step};
+
+    #[derive(Component)]
+    struct Reacted(PhysicsHandBone, Entity);
+
+    fn react(commands: &mut Commands, contact: BoneContact) {
+        commands.spawn(Reacted(contact.bone, contact.other));
+    }
+
+    #[test]
+    fn reactions_only_run_for_their_own_bone() {
+        let mut app = hand_app(HandPhysicsConfig::default());
+        let tip = bone_entity(&app, Hand::Right, PhysicsHandBone::IndexTip);
+        app.world.entity_mut(tip).insert(ContactReaction(react));
+
+        //big enough to touch the rest of the finger too
+        let position = bone_transform(&app, Hand::Right, PhysicsHandBone::IndexTip).translation;
+        let ball = spawn_ball(&mut app, position, 0.03);
+        //the second step keeps touching, which doesn't react again
+        step(&mut app, 2);
+
+        let contacts = events::<BoneContact>(&app);
+        assert!(contacts
+            .iter()
+            .any(|contact| contact.bone != PhysicsHandBone::IndexTip));
+
+        let mut reacted = app.world.query::<&Reacted>();
+        let reacted: Vec<_> = reacted.iter(&app.world).collect();
+        assert_eq!(reacted.len(), 1);
+        assert_eq!(reacted[0].0, PhysicsHandBone::IndexTip);
+        assert_eq!(reacted[0].1, ball);
+    }
+
+    // contacts of a right thumb tip pressed onto its own index tip and of a ball on the little tip
+    fn self_and_world_contacts(ignore_self_contacts: bool) -> (Vec<BoneContact>, Entity) {
+        let mut app = hand_app(HandPhysicsConfig {
+            ignore_self_contacts,
+            ..default()
+        });
+        //a hand normally filters itself out, let these two touch
+        let index_tip = bone_transform(&app, Hand::Right, PhysicsHandBone::IndexTip);
+        for bone in [PhysicsHandBone::IndexTip, PhysicsHandBone::ThumbTip] {
+            let entity = bone_entity(&app, Hand::Right, bone);
+            app.world.entity_mut(entity).insert((
+                CollisionGroups::new(Group::ALL, Group::ALL),
+                ActiveCollisionTypes::all(),
+            ));
+        }
+        let thumb_tip = bone_entity(&app, Hand::Right, PhysicsHandBone::ThumbTip);
+        *app.world.get_mut::<Transform>(thumb_tip).unwrap() = index_tip;
+
+        let little_tip = bone_transform(&app, Hand::Right, PhysicsHandBone::LittleTip).translation;
+        let ball = spawn_ball(&mut app, little_tip, 0.01);
+
+        //events only live for two frames, collect them as they come
+        let mut contacts = Vec::new();
+        for _ in 0..3 {
+            step(&mut app, 1);
+            contacts.extend(events::<BoneContact>(&app));
+        }
+        (contacts, ball)
+    }
+
+    #[test]
+    fn self_contacts_can_be_left_out() {
+        //the ball is nowhere near the thumb and index tips
+        let is_self = |contact: &BoneContact| {
+            matches!(
+                contact.bone,
+                PhysicsHandBone::ThumbTip | PhysicsHandBone::IndexTip
+            )
+        };
+
+        let (contacts, ball) = self_and_world_contacts(false);
+        assert!(contacts.iter().any(is_self));
+        assert!(contacts.iter().any(|contact| contact.other == ball));
+
+        let (contacts, ball) = self_and_world_contacts(true);
+        assert!(!contacts.iter().any(is_self));
+        assert!(contacts.iter().any(|contact| contact.other == ball));
+    }
+}
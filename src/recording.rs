@@ -0,0 +1,148 @@
+use bevy::prelude::*;
+use bevy_oxr::xr_input::hands::common::{HandBoneRadius, HandsResource};
+
+use crate::constants::{HandJoints, PhysicsHandBone};
+use crate::pose::get_tracked_hand_joints;
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HandFrame {
+    // seconds since startup, taken from Time::elapsed_seconds_f64
+    pub timestamp: f64,
+    pub left: HandJoints,
+    pub right: HandJoints,
+}
+
+#[derive(Resource, Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HandRecorder {
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub recording: bool,
+    pub frames: Vec<HandFrame>,
+}
+
+impl HandRecorder {
+    pub fn record(&mut self, timestamp: f64, left: HandJoints, right: HandJoints) {
+        self.frames.push(HandFrame {
+            timestamp,
+            left,
+            right,
+        });
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+}
+
+#[cfg(feature = "serde")]
+impl HandRecorder {
+    pub fn save_to(
+        &self,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let serialized = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        std::fs::write(path, serialized)?;
+        Ok(())
+    }
+
+    pub fn load_from(
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(ron::de::from_str(&contents)?)
+    }
+}
+
+pub fn record_hands(
+    mut recorder: ResMut<HandRecorder>,
+    hands_res: Option<Res<HandsResource>>,
+    hand_query: Query<(&Transform, &HandBoneRadius, Option<&Visibility>), Without<PhysicsHandBone>>,
+    time: Res<Time>,
+) {
+    if !recorder.recording {
+        return;
+    }
+
+    let Some(hands_res) = hands_res else {
+        return;
+    };
+
+    let left = get_tracked_hand_joints(&hands_res.left, &hand_query);
+    let right = get_tracked_hand_joints(&hands_res.right, &hand_query);
+
+    if let (Some(left), Some(right)) = (left, right) {
+        recorder.record(time.elapsed_seconds_f64(), left, right);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy_oxr::xr_input::Hand;
+
+    use super::*;
+    use crate::constants::get_default_hand;
+    use crate::test_utils::spawn_joint_entities;
+
+    #[test]
+    fn records_timestamped_frames_only_while_recording() {
+        let mut world = World::new();
+        let left = get_default_hand(&Hand::Left);
+        let right = get_default_hand(&Hand::Right);
+        spawn_joint_entities(&mut world, &left, &right);
+        world.insert_resource(HandRecorder {
+            recording: true,
+            ..default()
+        });
+        let mut time = Time::<()>::default();
+        time.advance_by(Duration::from_millis(250));
+        world.insert_resource(time);
+
+        world.run_system_once(record_hands);
+        world.resource_mut::<HandRecorder>().recording = false;
+        world.run_system_once(record_hands);
+
+        let recorder = world.resource::<HandRecorder>();
+        assert_eq!(recorder.frames.len(), 1);
+        assert_eq!(recorder.frames[0].timestamp, 0.25);
+        assert_eq!(
+            recorder.frames[0]
+                .right
+                .joint(PhysicsHandBone::IndexTip)
+                .position,
+            right.joint(PhysicsHandBone::IndexTip).position
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn saved_sessions_load_back() {
+        let mut recorder = HandRecorder::default();
+        recorder.record(
+            0.5,
+            get_default_hand(&Hand::Left),
+            get_default_hand(&Hand::Right),
+        );
+
+        let path = std::env::temp_dir().join(format!("xr_hand_session_{}.ron", std::process::id()));
+        recorder.save_to(&path).unwrap();
+        let loaded = HandRecorder::load_from(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(loaded.frames.len(), 1);
+        assert_eq!(loaded.frames[0].timestamp, 0.5);
+        assert_eq!(
+            loaded.frames[0]
+                .left
+                .joint(PhysicsHandBone::ThumbTip)
+                .position,
+            recorder.frames[0]
+                .left
+                .joint(PhysicsHandBone::ThumbTip)
+                .position
+        );
+    }
+}
@@ -0,0 +1,427 @@
+use bevy::{prelude::*, utils::HashMap};
+use bevy_oxr::xr_input::{
+    hands::{common::HandsResource, HandBone},
+    Hand,
+};
+
+use crate::config::{HandPhysicsConfig, RenderSource};
+use crate::constants::{
+    get_hand_resource_entity, Finger, NameToHandJoint, PhysicsHandBone, HAND_JOINT_COUNT,
+};
+use crate::debug::BoneTarget;
+use crate::tracking::TrackedHands;
+
+// the last two tracked transforms of a hand joint entity and the frame times they were seen at,
+// so fixed steps running between two frames can aim at an interpolated target
+#[derive(Component, Debug, Clone, Copy)]
+pub struct TargetHistory {
+    pub previous: Transform,
+    pub current: Transform,
+    pub previous_time: f64,
+    pub current_time: f64,
+}
+
+impl TargetHistory {
+    pub fn new(transform: Transform, time: f64) -> Self {
+        Self {
+            previous: transform,
+            current: transform,
+            previous_time: time,
+            current_time: time,
+        }
+    }
+
+    pub fn push(&mut self, transform: Transform, time: f64) {
+        self.previous = self.current;
+        self.previous_time = self.current_time;
+        self.current = transform;
+        self.current_time = time;
+    }
+
+    // linear velocity between the last two tracked transforms
+    pub fn velocity(&self) -> Vec3 {
+        let span = self.current_time - self.previous_time;
+        if span <= f64::EPSILON {
+            return Vec3::ZERO;
+        }
+        (self.current.translation - self.previous.translation) / span as f32
+    }
+
+    pub fn sample(&self, time: f64) -> Transform {
+        let span = self.current_time - self.previous_time;
+        if span <= f64::EPSILON {
+            return self.current;
+        }
+
+        let alpha = ((time - self.previous_time) / span).clamp(0.0, 1.0) as f32;
+        Transform {
+            translation: self
+                .previous
+                .translation
+                .lerp(self.current.translation, alpha),
+            rotation: self.previous.rotation.slerp(self.current.rotation, alpha),
+            scale: self.previous.scale.lerp(self.current.scale, alpha),
+        }
+    }
+}
+
+pub fn record_target_history(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut hand_query: Query<
+        (Entity, &Transform, Option<&mut TargetHistory>),
+        (With<HandBone>, Without<PhysicsHandBone>),
+    >,
+) {
+    let now = time.elapsed_seconds_f64();
+
+    for (entity, transform, history) in hand_query.iter_mut() {
+        match history {
+            Some(mut history) => history.push(*transform, now),
+            None => {
+                commands
+                    .entity(entity)
+                    .insert(TargetHistory::new(*transform, now));
+            }
+        }
+    }
+}
+
+// filtered wrist orientation per hand, see HandPhysicsConfig::wrist_smoothing
+#[derive(Resource, Debug, Clone, Default)]
+pub struct WristSmoothing {
+    pub left: Option<Quat>,
+    pub right: Option<Quat>,
+}
+
+// low pass the wrist rotation and turn the whole hand about the wrist with it before anything
+// downstream reads it, small wrist noise otherwise gets amplified down every finger
+pub fn stabilize_wrist(
+    config: Res<HandPhysicsConfig>,
+    hands_res: Option<Res<HandsResource>>,
+    mut smoothing: ResMut<WristSmoothing>,
+    mut hand_query: Query<&mut Transform, (With<HandBone>, Without<PhysicsHandBone>)>,
+) {
+    let Some(hands_res) = hands_res else {
+        return;
+    };
+
+    if config.wrist_smoothing <= 0.0 {
+        smoothing.left = None;
+        smoothing.right = None;
+        return;
+    }
+    let follow = 1.0 - config.wrist_smoothing.clamp(0.0, 0.99);
+
+    for (hand, hand_res) in [(Hand::Left, hands_res.left), (Hand::Right, hands_res.right)] {
+        let state = match hand {
+            Hand::Left => &mut smoothing.left,
+            Hand::Right => &mut smoothing.right,
+        };
+
+        let Ok(wrist) = hand_query.get(hand_res.wrist).copied() else {
+            continue;
+        };
+        let filtered = match state {
+            Some(previous) => previous.slerp(wrist.rotation, follow),
+            None => wrist.rotation,
+        };
+        *state = Some(filtered);
+
+        //whatever the filter took out of the wrist comes out of every joint of the hand
+        let correction = filtered * wrist.rotation.inverse();
+        for index in 0..HAND_JOINT_COUNT {
+            let bone = NameToHandJoint::get_physics_bone_from_index(index);
+            let Ok(mut transform) = hand_query.get_mut(get_hand_resource_entity(&hand_res, bone))
+            else {
+                continue;
+            };
+            transform.translation =
+                wrist.translation + correction * (transform.translation - wrist.translation);
+            transform.rotation = correction * transform.rotation;
+        }
+    }
+}
+
+// filtered transforms of the tracked finger joints, see HandPhysicsConfig::finger_smoothing
+#[derive(Resource, Debug, Clone, Default)]
+pub struct FingerSmoothing {
+    pub filtered: HashMap<Entity, Transform>,
+}
+
+pub fn smooth_fingers(
+    config: Res<HandPhysicsConfig>,
+    hands_res: Option<Res<HandsResource>>,
+    mut smoothing: ResMut<FingerSmoothing>,
+    mut hand_query: Query<&mut Transform, (With<HandBone>, Without<PhysicsHandBone>)>,
+) {
+    let Some(hands_res) = hands_res else {
+        return;
+    };
+
+    for hand_res in [hands_res.left, hands_res.right] {
+        for finger in Finger::ALL.iter() {
+            let amount = config.finger_smoothing[*finger as usize];
+
+            for bone in finger.bones().iter() {
+                let entity = get_hand_resource_entity(&hand_res, *bone);
+
+                if amount <= 0.0 {
+                    smoothing.filtered.remove(&entity);
+                    continue;
+                }
+                let follow = 1.0 - amount.clamp(0.0, 0.99);
+
+                let Ok(mut transform) = hand_query.get_mut(entity) else {
+                    continue;
+                };
+
+                let filtered = match smoothing.filtered.get(&entity) {
+                    Some(previous) => Transform {
+                        translation: previous.translation.lerp(transform.translation, follow),
+                        rotation: previous.rotation.slerp(transform.rotation, follow),
+                        scale: transform.scale,
+                    },
+                    None => *transform,
+                };
+
+                *transform = filtered;
+                smoothing.filtered.insert(entity, filtered);
+            }
+        }
+    }
+}
+
+// joint sphere drawn on a child of its bone rather than on the bone itself
+#[derive(Component, Debug, Clone, Copy)]
+pub struct JointVisual;
+
+// draws each joint at `physics_blend` between where tracking wants it and where the solver put it,
+// responsive like the raw target but still visibly stopped by what it touches. with
+// RenderSource::Tracked the joints sit on the raw tracked pose instead
+pub fn blend_joint_visuals(
+    config: Res<HandPhysicsConfig>,
+    tracked_hands: Res<TrackedHands>,
+    bone_query: Query<(&GlobalTransform, &BoneTarget, &PhysicsHandBone, &Hand)>,
+    mut visual_query: Query<(&Parent, &mut Transform), With<JointVisual>>,
+) {
+    let blend = config.physics_blend.clamp(0.0, 1.0);
+
+    for (parent, mut transform) in visual_query.iter_mut() {
+        let Ok((bone_transform, target, bone, hand)) = bone_query.get(parent.get()) else {
+            continue;
+        };
+        let to_local = bone_transform.affine().inverse();
+
+        let (translation, rotation) = match config.render_source {
+            RenderSource::Tracked => match tracked_hands.get(*hand) {
+                Some(joints) => {
+                    let joint = joints.joint(*bone);
+                    (
+                        to_local.transform_point3(joint.position),
+                        bone_transform.to_scale_rotation_translation().1.inverse()
+                            * joint.orientation,
+                    )
+                }
+                None => (Vec3::ZERO, Quat::IDENTITY),
+            },
+            //the target only exists once the bone has been driven
+            RenderSource::Physics if blend >= 1.0 || *target == BoneTarget::default() => {
+                (Vec3::ZERO, Quat::IDENTITY)
+            }
+            RenderSource::Physics => {
+                let blended = target
+                    .0
+                    .translation
+                    .lerp(bone_transform.translation(), blend);
+                (to_local.transform_point3(blended), Quat::IDENTITY)
+            }
+        };
+
+        if transform.translation != translation || transform.rotation != rotation {
+            transform.translation = translation;
+            transform.rotation = rotation;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::constants::{get_default_hand, HandJoints};
+    use crate::pose::HandOrigin;
+    use crate::test_utils::{move_joint_entities, spawn_joint_entities};
+
+    #[test]
+    fn history_interpolates_between_the_last_two_frames() {
+        let mut history = TargetHistory::new(Transform::from_xyz(0.0, 0.0, 0.0), 1.0);
+        history.push(Transform::from_xyz(1.0, 0.0, 0.0), 1.1);
+
+        assert!((history.sample(1.05).translation.x - 0.5).abs() < 1e-5);
+        //clamped to the frames it knows rather than extrapolated
+        assert_eq!(history.sample(0.9).translation.x, 0.0);
+        assert_eq!(history.sample(1.2).translation.x, 1.0);
+        assert!((history.velocity().x - 10.0).abs() < 1e-3);
+    }
+
+    // summed frame to frame travel of the right index tip under a wrist that wobbles about its
+    // own position
+    fn index_tip_travel(wrist_smoothing: f32) -> f32 {
+        let mut world = World::new();
+        spawn_joint_entities(
+            &mut world,
+            &get_default_hand(&Hand::Left),
+            &get_default_hand(&Hand::Right),
+        );
+        world.insert_resource(HandPhysicsConfig {
+            wrist_smoothing,
+            ..default()
+        });
+        world.init_resource::<WristSmoothing>();
+
+        let tip = world.resource::<HandsResource>().right.index.tip;
+        let wrist = Transform::from_xyz(0.2, 1.0, -0.3);
+        let mut last = None;
+        let mut travel = 0.0;
+        for frame in 0..20 {
+            let noise = if frame % 2 == 0 { 0.05 } else { -0.05 };
+            let noisy = wrist.with_rotation(Quat::from_rotation_y(noise));
+            move_joint_entities(
+                &mut world,
+                Hand::Right,
+                &get_default_hand(&Hand::Right).anchored_at_wrist(&noisy),
+            );
+            world.run_system_once(stabilize_wrist);
+
+            let position = world.get::<Transform>(tip).unwrap().translation;
+            if let Some(last) = last {
+                travel += position.distance(last);
+            }
+            last = Some(position);
+        }
+        travel
+    }
+
+    #[test]
+    fn wrist_smoothing_steadies_the_fingertips() {
+        let raw = index_tip_travel(0.0);
+        assert!(raw > 0.0);
+        assert!(index_tip_travel(0.8) < raw * 0.5);
+    }
+
+    #[test]
+    fn each_finger_lags_by_its_own_smoothing() {
+        let mut world = World::new();
+        spawn_joint_entities(
+            &mut world,
+            &get_default_hand(&Hand::Left),
+            &get_default_hand(&Hand::Right),
+        );
+        world.insert_resource(HandPhysicsConfig {
+            finger_smoothing: [0.0, 0.1, 0.0, 0.0, 0.9],
+            ..default()
+        });
+        world.init_resource::<FingerSmoothing>();
+        world.run_system_once(smooth_fingers);
+
+        let moved = get_default_hand(&Hand::Right)
+            .transformed(&HandOrigin(Transform::from_xyz(0.1, 0.0, 0.0)));
+        move_joint_entities(&mut world, Hand::Right, &moved);
+        world.run_system_once(smooth_fingers);
+
+        let hand_res = world.resource::<HandsResource>().right;
+        let lag = |world: &World, bone: PhysicsHandBone| {
+            let entity = get_hand_resource_entity(&hand_res, bone);
+            let actual = world.get::<Transform>(entity).unwrap().translation;
+            actual.distance(moved.joint(bone).position)
+        };
+        let index = lag(&world, PhysicsHandBone::IndexTip);
+        let little = lag(&world, PhysicsHandBone::LittleTip);
+        assert!(lag(&world, PhysicsHandBone::MiddleTip) < 1e-6);
+        assert!((index - 0.01).abs() < 1e-4);
+        assert!((little - 0.09).abs() < 1e-4);
+    }
+
+    // where a joint is drawn, with its bone on the origin and the bone's target 10cm along x
+    fn blended_visual(physics_blend: f32) -> Vec3 {
+        let mut world = World::new();
+        world.insert_resource(HandPhysicsConfig {
+            physics_blend,
+            ..default()
+        });
+        world.init_resource::<TrackedHands>();
+        let mut visual = None;
+        world
+            .spawn((
+                GlobalTransform::IDENTITY,
+                BoneTarget(Transform::from_xyz(0.1, 0.0, 0.0)),
+                PhysicsHandBone::IndexTip,
+                Hand::Right,
+            ))
+            .with_children(|parent| {
+                visual = Some(parent.spawn((JointVisual, Transform::default())).id());
+            });
+
+        world.run_system_once(blend_joint_visuals);
+        world.get::<Transform>(visual.unwrap()).unwrap().translation
+    }
+
+    #[test]
+    fn visuals_blend_from_the_target_to_the_bone() {
+        assert!(blended_visual(0.0).distance(Vec3::new(0.1, 0.0, 0.0)) < 1e-6);
+        assert_eq!(blended_visual(1.0), Vec3::ZERO);
+        assert!(blended_visual(0.25).distance(Vec3::new(0.075, 0.0, 0.0)) < 1e-6);
+    }
+
+    // the visual's pose relative to a bone rotated and moved away from the origin
+    fn visual_for(render_source: RenderSource, tracked: Option<HandJoints>) -> Transform {
+        let mut world = World::new();
+        world.insert_resource(HandPhysicsConfig {
+            render_source,
+            ..default()
+        });
+        world.insert_resource(TrackedHands {
+            left: None,
+            right: tracked,
+        });
+        let bone = Transform::from_xyz(0.2, 1.0, -0.3).with_rotation(Quat::from_rotation_y(0.3));
+        let mut visual = None;
+        world
+            .spawn((
+                GlobalTransform::from(bone),
+                BoneTarget(Transform::from_xyz(0.1, 0.0, 0.0)),
+                PhysicsHandBone::IndexTip,
+                Hand::Right,
+            ))
+            .with_children(|parent| {
+                visual = Some(parent.spawn((JointVisual, Transform::default())).id());
+            });
+
+        world.run_system_once(blend_joint_visuals);
+        *world.get::<Transform>(visual.unwrap()).unwrap()
+    }
+
+    #[test]
+    fn visuals_follow_the_chosen_render_source() {
+        let bone = Transform::from_xyz(0.2, 1.0, -0.3).with_rotation(Quat::from_rotation_y(0.3));
+        let tracked = get_default_hand(&Hand::Right);
+        let joint = tracked.joint(PhysicsHandBone::IndexTip);
+
+        //tracked, the visual lands on the raw joint wherever the bone is
+        let local = visual_for(RenderSource::Tracked, Some(tracked));
+        let global = bone * local;
+        assert!(global.translation.distance(joint.position) < 1e-5);
+        assert!(global.rotation.angle_between(joint.orientation) < 1e-4);
+
+        //physics, it stays on the bone
+        let local = visual_for(RenderSource::Physics, Some(tracked));
+        assert_eq!(local.translation, Vec3::ZERO);
+        assert_eq!(local.rotation, Quat::IDENTITY);
+
+        //nothing tracked to show, back on the bone
+        let local = visual_for(RenderSource::Tracked, None);
+        assert_eq!(local.translation, Vec3::ZERO);
+    }
+}
@@ -0,0 +1,237 @@
+// shared setup for the unit tests: a headless app running the hand plugin on the test clock, and
+// stand-ins for the joint entities bevy_oxr would otherwise spawn and track
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use bevy::{
+    ecs::system::RunSystemOnce,
+    log::tracing_subscriber::{
+        layer::{Context, SubscriberExt},
+        Layer, Registry,
+    },
+    prelude::*,
+    scene::ScenePlugin,
+    utils::tracing::{self, field::Field, Subscriber},
+};
+use bevy_oxr::xr_input::{
+    hands::{
+        common::{HandBoneRadius, HandResource, HandsResource},
+        HandBone,
+    },
+    Hand,
+};
+use bevy_rapier3d::prelude::*;
+
+use crate::clock::{advance_time, run_fixed_steps, TestClockPlugin};
+use crate::config::HandPhysicsConfig;
+use crate::constants::{
+    get_hand_resource_entity, update_physics_hands, HandJoints, NameToHandJoint, PhysicsHandBone,
+    PhysicsHandsResource, HAND_JOINT_COUNT,
+};
+use crate::driven::DrivenPose;
+use crate::plugin::XrHandPhysicsPlugin;
+use crate::provider::{HandTargetProvider, HandTargetSource};
+
+// the plugin with `config` and nothing xr, already past Startup so both hands are spawned
+pub fn hand_app(config: HandPhysicsConfig) -> App {
+    hand_app_with(config, XrHandPhysicsPlugin::default())
+}
+
+pub fn hand_app_with(config: HandPhysicsConfig, plugin: XrHandPhysicsPlugin) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        TransformPlugin,
+        HierarchyPlugin,
+        AssetPlugin::default(),
+        ScenePlugin,
+    ))
+    .init_asset::<Mesh>()
+    .init_asset::<StandardMaterial>()
+    .insert_resource(config)
+    .add_plugins((plugin, TestClockPlugin));
+
+    //the debug run conditions look the gizmo groups up even with nothing to draw
+    #[cfg(feature = "debug")]
+    app.init_resource::<bevy::gizmos::config::GizmoConfigStore>();
+
+    advance_time(&mut app, Duration::ZERO);
+    app
+}
+
+pub fn step(app: &mut App, steps: u32) {
+    run_fixed_steps(app, steps);
+}
+
+pub fn bone_entity(app: &App, hand: Hand, bone: PhysicsHandBone) -> Entity {
+    app.world
+        .resource::<PhysicsHandsResource>()
+        .get(hand, bone)
+        .expect("bone wasn't spawned")
+}
+
+pub fn bone_transform(app: &App, hand: Hand, bone: PhysicsHandBone) -> Transform {
+    *app.world
+        .get::<Transform>(bone_entity(app, hand, bone))
+        .expect("bone has no transform")
+}
+
+// feed `joints` to a hand through DrivenPose, the way a deterministic tracking source would
+pub fn drive(app: &mut App, hand: Hand, joints: HandJoints) {
+    let mut driven_pose = app.world.resource_mut::<DrivenPose>();
+    match hand {
+        Hand::Left => driven_pose.left = Some(joints),
+        Hand::Right => driven_pose.right = Some(joints),
+    }
+}
+
+// a HandTargetProvider holding each hand still on one pose
+pub struct FixedTargets {
+    pub left: HandJoints,
+    pub right: HandJoints,
+}
+
+impl HandTargetProvider for FixedTargets {
+    fn targets(&self, hand: Hand) -> Option<HandJoints> {
+        match hand {
+            Hand::Left => Some(self.left),
+            Hand::Right => Some(self.right),
+        }
+    }
+}
+
+// run update_physics_hands once, outside its run condition, aiming the hands at `left` and `right`
+pub fn drive_once(app: &mut App, left: HandJoints, right: HandJoints) {
+    app.insert_resource(HandTargetSource::new(FixedTargets { left, right }));
+    step(app, 1);
+    app.world.run_system_once(update_physics_hands);
+}
+
+// a weightless dynamic ball, dynamic so rapier computes solver contacts against the fixed bones
+pub fn spawn_ball(app: &mut App, position: Vec3, radius: f32) -> Entity {
+    app.world
+        .spawn((
+            TransformBundle::from_transform(Transform::from_translation(position)),
+            RigidBody::Dynamic,
+            GravityScale(0.0),
+            Collider::ball(radius),
+            ActiveEvents::COLLISION_EVENTS,
+            ActiveCollisionTypes::all(),
+        ))
+        .id()
+}
+
+// every event of type `E` sent since the app started, or since they were last cleared
+pub fn events<E: Event + Clone>(app: &App) -> Vec<E> {
+    let events = app.world.resource::<Events<E>>();
+    events.get_reader().read(events).cloned().collect()
+}
+
+pub fn hand_resource_entity_mut(hand_res: &mut HandResource, bone: PhysicsHandBone) -> &mut Entity {
+    match bone {
+        PhysicsHandBone::Palm => &mut hand_res.palm,
+        PhysicsHandBone::Wrist => &mut hand_res.wrist,
+        PhysicsHandBone::ThumbMetacarpal => &mut hand_res.thumb.metacarpal,
+        PhysicsHandBone::ThumbProximal => &mut hand_res.thumb.proximal,
+        PhysicsHandBone::ThumbDistal => &mut hand_res.thumb.distal,
+        PhysicsHandBone::ThumbTip => &mut hand_res.thumb.tip,
+        PhysicsHandBone::IndexMetacarpal => &mut hand_res.index.metacarpal,
+        PhysicsHandBone::IndexProximal => &mut hand_res.index.proximal,
+        PhysicsHandBone::IndexIntermediate => &mut hand_res.index.intermediate,
+        PhysicsHandBone::IndexDistal => &mut hand_res.index.distal,
+        PhysicsHandBone::IndexTip => &mut hand_res.index.tip,
+        PhysicsHandBone::MiddleMetacarpal => &mut hand_res.middle.metacarpal,
+        PhysicsHandBone::MiddleProximal => &mut hand_res.middle.proximal,
+        PhysicsHandBone::MiddleIntermediate => &mut hand_res.middle.intermediate,
+        PhysicsHandBone::MiddleDistal => &mut hand_res.middle.distal,
+        PhysicsHandBone::MiddleTip => &mut hand_res.middle.tip,
+        PhysicsHandBone::RingMetacarpal => &mut hand_res.ring.metacarpal,
+        PhysicsHandBone::RingProximal => &mut hand_res.ring.proximal,
+        PhysicsHandBone::RingIntermediate => &mut hand_res.ring.intermediate,
+        PhysicsHandBone::RingDistal => &mut hand_res.ring.distal,
+        PhysicsHandBone::RingTip => &mut hand_res.ring.tip,
+        PhysicsHandBone::LittleMetacarpal => &mut hand_res.little.metacarpal,
+        PhysicsHandBone::LittleProximal => &mut hand_res.little.proximal,
+        PhysicsHandBone::LittleIntermediate => &mut hand_res.little.intermediate,
+        PhysicsHandBone::LittleDistal => &mut hand_res.little.distal,
+        PhysicsHandBone::LittleTip => &mut hand_res.little.tip,
+    }
+}
+
+// joint entities placed on `left` and `right` like bevy_oxr keeps them, plus their HandsResource
+pub fn spawn_joint_entities(world: &mut World, left: &HandJoints, right: &HandJoints) {
+    let mut hands_res = HandsResource::default();
+
+    for (hand, joints) in [(Hand::Left, left), (Hand::Right, right)] {
+        let hand_res = match hand {
+            Hand::Left => &mut hands_res.left,
+            Hand::Right => &mut hands_res.right,
+        };
+
+        for (index, joint) in joints.inner.iter().enumerate() {
+            let bone = NameToHandJoint::get_physics_bone_from_index(index);
+            let hand_bone = HandBone::get_all_bones()
+                .into_iter()
+                .find(|hand_bone| hand_bone.get_index_from_bone() == index)
+                .expect("bevy_oxr has every joint");
+
+            let entity = world
+                .spawn((
+                    TransformBundle::from_transform(
+                        Transform::from_translation(joint.position)
+                            .with_rotation(joint.orientation),
+                    ),
+                    VisibilityBundle::default(),
+                    HandBoneRadius(joint.radius),
+                    hand_bone,
+                    hand,
+                ))
+                .id();
+            *hand_resource_entity_mut(hand_res, bone) = entity;
+        }
+    }
+
+    world.insert_resource(hands_res);
+}
+
+// move the joint entities of `hand` onto `joints`
+pub fn move_joint_entities(world: &mut World, hand: Hand, joints: &HandJoints) {
+    let hand_res = match hand {
+        Hand::Left => world.resource::<HandsResource>().left,
+        Hand::Right => world.resource::<HandsResource>().right,
+    };
+
+    for index in 0..HAND_JOINT_COUNT {
+        let bone = NameToHandJoint::get_physics_bone_from_index(index);
+        let entity = get_hand_resource_entity(&hand_res, bone);
+        if let Some(mut transform) = world.get_mut::<Transform>(entity) {
+            transform.translation = joints.inner[index].position;
+            transform.rotation = joints.inner[index].orientation;
+        }
+    }
+}
+
+// runs `f` with every tracing event raised on this thread collected as "field=value" lines, led
+// by the event's level and target
+pub fn capture_tracing<R>(f: impl FnOnce() -> R) -> (R, Vec<String>) {
+    let captured = Arc::new(Mutex::new(Vec::new()));
+    let subscriber = Registry::default().with(EventCapture(captured.clone()));
+    let result = tracing::subscriber::with_default(subscriber, f);
+    let lines = captured.lock().unwrap().clone();
+    (result, lines)
+}
+
+struct EventCapture(Arc<Mutex<Vec<String>>>);
+
+impl<S: Subscriber> Layer<S> for EventCapture {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut line = format!("level={} target={} ", metadata.level(), metadata.target());
+        event.record(&mut |field: &Field, value: &dyn std::fmt::Debug| {
+            line.push_str(&format!("{}={:?} ", field.name(), value));
+        });
+        self.0.lock().unwrap().push(line);
+    }
+}
@@ -0,0 +1,125 @@
+use bevy::prelude::*;
+use bevy_oxr::xr_input::Hand;
+use bevy_rapier3d::prelude::*;
+
+use crate::config::HandPhysicsConfig;
+use crate::constants::PhysicsHandBone;
+
+// marker on the five fingertip bones of each hand
+#[derive(Component)]
+pub struct TipBone;
+
+// solver group of fingertips that are currently too close to each other, merged tips are only
+// members of this group and filter it out, so they don't solve contacts against one another
+pub const MERGED_TIP_GROUP: Group = Group::GROUP_10;
+
+#[derive(Component)]
+pub struct MergedTip;
+
+// membership of the merged group alone, keeping the configured filter minus the merged group.
+// the default bone membership is Group::ALL, which already contains the merged group, so adding
+// it to that would leave merged tips solving against each other like before
+pub fn merged_tip_solver_groups(config: &HandPhysicsConfig) -> SolverGroups {
+    let mut filter = config.solver_filter;
+    filter.remove(MERGED_TIP_GROUP);
+    SolverGroups::new(MERGED_TIP_GROUP, filter)
+}
+
+pub fn merge_close_fingertips(
+    mut commands: Commands,
+    config: Res<HandPhysicsConfig>,
+    tip_query: Query<
+        (
+            Entity,
+            &Transform,
+            &PhysicsHandBone,
+            &Hand,
+            Option<&MergedTip>,
+        ),
+        With<TipBone>,
+    >,
+) {
+    let tips: Vec<_> = tip_query.iter().collect();
+
+    for (entity, transform, bone, hand, merged) in tips.iter() {
+        //a threshold of zero turns merging off and un-merges everything
+        let should_merge = config.fingertip_merge_distance > 0.0
+            && tips
+                .iter()
+                .any(|(other, other_transform, other_bone, other_hand, _)| {
+                    other != entity
+                        && (other_bone != bone || other_hand != hand)
+                        && transform.translation.distance(other_transform.translation)
+                            < config.fingertip_merge_distance
+                });
+
+        match (should_merge, merged.is_some()) {
+            (true, false) => {
+                commands
+                    .entity(*entity)
+                    .insert((MergedTip, merged_tip_solver_groups(&config)));
+            }
+            (false, true) => {
+                commands
+                    .entity(*entity)
+                    .remove::<MergedTip>()
+                    .insert(config.solver_groups());
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::test_utils::{bone_entity, hand_app};
+
+    fn solves(a: SolverGroups, b: SolverGroups) -> bool {
+        a.memberships.intersects(b.filters) && b.memberships.intersects(a.filters)
+    }
+
+    #[test]
+    fn merged_tips_stop_solving_against_each_other_only() {
+        let config = HandPhysicsConfig::default();
+        let merged = merged_tip_solver_groups(&config);
+
+        assert!(!solves(merged, merged));
+        assert!(solves(merged, config.solver_groups()));
+        assert!(solves(merged, SolverGroups::default()));
+    }
+
+    #[test]
+    fn tips_merge_once_they_come_close() {
+        let mut app = hand_app(HandPhysicsConfig {
+            fingertip_merge_distance: 0.015,
+            ..Default::default()
+        });
+        let thumb = bone_entity(&app, Hand::Right, PhysicsHandBone::ThumbTip);
+        let index = bone_entity(&app, Hand::Right, PhysicsHandBone::IndexTip);
+
+        let index_position = app.world.get::<Transform>(index).unwrap().translation;
+        app.world.get_mut::<Transform>(thumb).unwrap().translation =
+            index_position + Vec3::X * 0.005;
+        app.world.run_system_once(merge_close_fingertips);
+
+        for tip in [thumb, index] {
+            assert!(app.world.get::<MergedTip>(tip).is_some());
+            assert_eq!(
+                app.world.get::<SolverGroups>(tip).unwrap().memberships,
+                MERGED_TIP_GROUP
+            );
+        }
+    }
+
+    #[test]
+    fn merging_is_off_by_default() {
+        let mut app = hand_app(HandPhysicsConfig::default());
+        app.world.run_system_once(merge_close_fingertips);
+
+        let mut merged = app.world.query_filtered::<Entity, With<MergedTip>>();
+        assert_eq!(merged.iter(&app.world).count(), 0);
+    }
+}
@@ -0,0 +1,704 @@
+use bevy::{prelude::*, utils::HashMap};
+use bevy_oxr::xr_input::{
+    hands::{
+        common::{HandBoneRadius, HandsResource},
+        HandBone,
+    },
+    Hand,
+};
+use bevy_rapier3d::dynamics::Velocity;
+
+use crate::config::{HandPhysicsConfig, TrackingLossBehavior};
+use crate::constants::{
+    get_default_hand, get_hand_resource_entity, BoneInitState, Finger, HandJoints, NameToHandJoint,
+    PhysicsHandBone, HAND_JOINT_COUNT,
+};
+use crate::driven::{DrivenPose, WristAnchor};
+use crate::pose::get_tracked_hand_joints;
+use crate::provider::HandTargetSource;
+use crate::targets::{FingerSmoothing, TargetHistory, WristSmoothing};
+
+// latest pose per hand as reported by the tracking source, None while a hand isn't seen at all
+#[derive(Resource, Debug, Clone, Default)]
+pub struct TrackedHands {
+    pub left: Option<HandJoints>,
+    pub right: Option<HandJoints>,
+}
+
+impl TrackedHands {
+    pub fn get(&self, hand: Hand) -> Option<&HandJoints> {
+        match hand {
+            Hand::Left => self.left.as_ref(),
+            Hand::Right => self.right.as_ref(),
+        }
+    }
+
+    pub fn get_mut(&mut self, hand: Hand) -> &mut Option<HandJoints> {
+        match hand {
+            Hand::Left => &mut self.left,
+            Hand::Right => &mut self.right,
+        }
+    }
+}
+
+// most recent pose per hand where every joint was valid and tracked
+#[derive(Resource, Debug, Clone, Default)]
+pub struct LastGoodPose {
+    pub left: Option<HandJoints>,
+    pub right: Option<HandJoints>,
+}
+
+impl LastGoodPose {
+    pub fn get(&self, hand: Hand) -> Option<&HandJoints> {
+        match hand {
+            Hand::Left => self.left.as_ref(),
+            Hand::Right => self.right.as_ref(),
+        }
+    }
+
+    pub fn set(&mut self, hand: Hand, joints: HandJoints) {
+        match hand {
+            Hand::Left => self.left = Some(joints),
+            Hand::Right => self.right = Some(joints),
+        }
+    }
+}
+
+impl HandJoints {
+    pub fn is_fully_tracked(&self) -> bool {
+        self.inner.iter().all(|joint| {
+            joint.position_valid
+                && joint.position_tracked
+                && joint.orientation_valid
+                && joint.orientation_tracked
+        })
+    }
+
+    // same pose flagged as not tracked, so it's never mistaken for live data
+    pub fn untracked(&self) -> HandJoints {
+        HandJoints {
+            inner: self.inner.map(|mut joint| {
+                joint.position_tracked = false;
+                joint.orientation_tracked = false;
+                joint
+            }),
+        }
+    }
+}
+
+// last transform seen per joint entity and for how many frames in a row it has been seen, see
+// HandPhysicsConfig::stale_joint_frames
+#[derive(Default)]
+pub struct JointStaleness(HashMap<Entity, (Transform, u32)>);
+
+impl JointStaleness {
+    fn observe(&mut self, entity: Entity, transform: Transform) -> u32 {
+        match self.0.get_mut(&entity) {
+            Some((last, frames)) if *last == transform => {
+                *frames += 1;
+                *frames
+            }
+            _ => {
+                self.0.insert(entity, (transform, 0));
+                0
+            }
+        }
+    }
+}
+
+pub fn collect_tracked_hands(
+    config: Res<HandPhysicsConfig>,
+    hands_res: Option<Res<HandsResource>>,
+    hand_query: Query<(&Transform, &HandBoneRadius, Option<&Visibility>), Without<PhysicsHandBone>>,
+    driven_pose: Res<DrivenPose>,
+    anchor_query: Query<(&WristAnchor, &Hand)>,
+    mut staleness: Local<JointStaleness>,
+    mut tracked_hands: ResMut<TrackedHands>,
+) {
+    for hand in [Hand::Left, Hand::Right] {
+        let Some(hands_res) = hands_res.as_ref() else {
+            *tracked_hands.get_mut(hand) = None;
+            continue;
+        };
+        let hand_res = match hand {
+            Hand::Left => hands_res.left,
+            Hand::Right => hands_res.right,
+        };
+
+        let mut joints = get_tracked_hand_joints(&hand_res, &hand_query);
+        if let Some(joints) = joints.as_mut().filter(|_| config.stale_joint_frames > 0) {
+            for (index, joint) in joints.inner.iter_mut().enumerate() {
+                let entity = get_hand_resource_entity(
+                    &hand_res,
+                    NameToHandJoint::get_physics_bone_from_index(index),
+                );
+                let Ok((transform, ..)) = hand_query.get(entity) else {
+                    continue;
+                };
+                if staleness.observe(entity, *transform) >= config.stale_joint_frames {
+                    joint.position_tracked = false;
+                    joint.orientation_tracked = false;
+                }
+            }
+        }
+        *tracked_hands.get_mut(hand) = joints;
+    }
+
+    //anchored and driven hands are exact and hold still on purpose, they count as tracked
+    for (anchor, hand) in anchor_query.iter() {
+        *tracked_hands.get_mut(*hand) = Some(get_default_hand(hand).anchored_at_wrist(&anchor.0));
+    }
+    for hand in [Hand::Left, Hand::Right] {
+        if let Some(joints) = driven_pose.get(hand) {
+            *tracked_hands.get_mut(hand) = Some(*joints);
+        }
+    }
+}
+
+// per joint velocity over the last frame from the two most recent TrackedHands, zero for joints
+// that weren't position tracked both times. None until a hand has been seen two frames in a row.
+// unlike the bone velocities this is what the user's hand did, not what the solver let it do
+#[derive(Resource, Debug, Clone, Default)]
+pub struct TrackedVelocities {
+    pub left: Option<[Vec3; HAND_JOINT_COUNT]>,
+    pub right: Option<[Vec3; HAND_JOINT_COUNT]>,
+}
+
+impl TrackedVelocities {
+    pub fn get(&self, hand: Hand) -> Option<&[Vec3; HAND_JOINT_COUNT]> {
+        match hand {
+            Hand::Left => self.left.as_ref(),
+            Hand::Right => self.right.as_ref(),
+        }
+    }
+
+    pub fn get_mut(&mut self, hand: Hand) -> &mut Option<[Vec3; HAND_JOINT_COUNT]> {
+        match hand {
+            Hand::Left => &mut self.left,
+            Hand::Right => &mut self.right,
+        }
+    }
+
+    pub fn joint(&self, hand: Hand, bone: PhysicsHandBone) -> Option<Vec3> {
+        self.get(hand).map(|velocities| velocities[bone.index()])
+    }
+}
+
+pub fn update_tracked_velocities(
+    time: Res<Time>,
+    tracked_hands: Res<TrackedHands>,
+    mut previous: Local<TrackedHands>,
+    mut velocities: ResMut<TrackedVelocities>,
+) {
+    let delta = time.delta_seconds();
+
+    for hand in [Hand::Left, Hand::Right] {
+        *velocities.get_mut(hand) = match (previous.get(hand), tracked_hands.get(hand)) {
+            (Some(before), Some(now)) if delta > 0.0 => Some(std::array::from_fn(|index| {
+                let (before, now) = (before.inner[index], now.inner[index]);
+                if before.position_tracked && now.position_tracked {
+                    (now.position - before.position) / delta
+                } else {
+                    Vec3::ZERO
+                }
+            })),
+            _ => None,
+        };
+    }
+
+    *previous = tracked_hands.clone();
+}
+
+// run condition, false while neither hand reports a single tracked joint (hands down, controllers
+// in use) so the bones aren't driven towards a pose nobody is holding. fallback poses are flagged
+// untracked so they don't count. reset_accumulators_on_resume takes care of picking back up.
+// a HandTargetSource stands in for tracking, it decides per hand whether there's anything to drive
+pub fn any_hand_tracked(
+    tracked_hands: Res<TrackedHands>,
+    target_source: Option<Res<HandTargetSource>>,
+) -> bool {
+    if target_source.is_some() {
+        return true;
+    }
+
+    [Hand::Left, Hand::Right].into_iter().any(|hand| {
+        tracked_hands.get(hand).is_some_and(|joints| {
+            joints
+                .inner
+                .iter()
+                .any(|joint| joint.position_tracked || joint.orientation_tracked)
+        })
+    })
+}
+
+pub fn spawn_at_startup(config: Res<HandPhysicsConfig>) -> bool {
+    config.min_tracked_joints == 0
+}
+
+// run condition for the deferred spawn, true once either hand has enough position tracked joints.
+// has to see the flags collect_tracked_hands read, hold_pose_on_tracking_loss replaces a partly
+// tracked hand with a fallback that counts as nothing tracked
+pub fn enough_joints_tracked(
+    config: Res<HandPhysicsConfig>,
+    tracked_hands: Res<TrackedHands>,
+) -> bool {
+    [Hand::Left, Hand::Right].into_iter().any(|hand| {
+        tracked_hands.get(hand).is_some_and(|joints| {
+            joints
+                .inner
+                .iter()
+                .filter(|joint| joint.position_tracked)
+                .count()
+                >= config.min_tracked_joints
+        })
+    })
+}
+
+pub fn update_last_good_pose(
+    tracked_hands: Res<TrackedHands>,
+    mut last_good_pose: ResMut<LastGoodPose>,
+) {
+    for hand in [Hand::Left, Hand::Right] {
+        if let Some(joints) = tracked_hands.get(hand) {
+            if joints.is_fully_tracked() {
+                last_good_pose.set(hand, *joints);
+            }
+        }
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct HandTrackingLost {
+    pub hand: Hand,
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct HandTrackingStarted {
+    pub hand: Hand,
+}
+
+// has to run before hold_pose_on_tracking_loss fills the gaps with the fallback pose
+pub fn detect_tracking_transitions(
+    tracked_hands: Res<TrackedHands>,
+    mut was_tracked: Local<[bool; 2]>,
+    mut lost_events: EventWriter<HandTrackingLost>,
+    mut started_events: EventWriter<HandTrackingStarted>,
+) {
+    for (index, hand) in [Hand::Left, Hand::Right].into_iter().enumerate() {
+        let tracked = tracked_hands
+            .get(hand)
+            .map(|joints| joints.is_fully_tracked())
+            .unwrap_or(false);
+
+        match (was_tracked[index], tracked) {
+            (true, false) => {
+                lost_events.send(HandTrackingLost { hand });
+            }
+            (false, true) => {
+                started_events.send(HandTrackingStarted { hand });
+            }
+            _ => {}
+        }
+        was_tracked[index] = tracked;
+    }
+}
+
+// anything filtered or integrated over time still remembers where the hand was before it was lost,
+// drop all of it on resume so the bones don't lurch across the gap on the first step
+pub fn reset_accumulators_on_resume(
+    mut started_events: EventReader<HandTrackingStarted>,
+    time: Res<Time>,
+    hands_res: Option<Res<HandsResource>>,
+    mut wrist_smoothing: ResMut<WristSmoothing>,
+    mut finger_smoothing: ResMut<FingerSmoothing>,
+    mut history_query: Query<(&Transform, &Hand, &mut TargetHistory), With<HandBone>>,
+    mut bone_query: Query<
+        (&Hand, &mut Velocity, Option<&mut BoneInitState>),
+        With<PhysicsHandBone>,
+    >,
+) {
+    let now = time.elapsed_seconds_f64();
+
+    for event in started_events.read() {
+        match event.hand {
+            Hand::Left => wrist_smoothing.left = None,
+            Hand::Right => wrist_smoothing.right = None,
+        }
+
+        if let Some(hands_res) = hands_res.as_ref() {
+            let hand_res = match event.hand {
+                Hand::Left => hands_res.left,
+                Hand::Right => hands_res.right,
+            };
+            for finger in Finger::ALL.iter() {
+                for bone in finger.bones().iter() {
+                    finger_smoothing
+                        .filtered
+                        .remove(&get_hand_resource_entity(&hand_res, *bone));
+                }
+            }
+        }
+
+        for (transform, hand, mut history) in history_query.iter_mut() {
+            if *hand == event.hand {
+                *history = TargetHistory::new(*transform, now);
+            }
+        }
+
+        for (hand, mut velocity, init_state) in bone_query.iter_mut() {
+            if *hand != event.hand {
+                continue;
+            }
+
+            *velocity = Velocity::zero();
+            if let Some(mut init_state) = init_state {
+                *init_state = BoneInitState::False;
+            }
+        }
+    }
+}
+
+// what a hand shows while it isn't tracked, None to keep following the tracking source
+pub fn fallback_pose(
+    hand: Hand,
+    behavior: TrackingLossBehavior,
+    last_good_pose: &LastGoodPose,
+) -> Option<HandJoints> {
+    let pose = match behavior {
+        TrackingLossBehavior::FollowSource => return None,
+        TrackingLossBehavior::HoldLastGoodPose => last_good_pose
+            .get(hand)
+            .copied()
+            .unwrap_or_else(|| get_default_hand(&hand)),
+        TrackingLossBehavior::Default => get_default_hand(&hand),
+    };
+    Some(pose.untracked())
+}
+
+pub fn hold_pose_on_tracking_loss(
+    config: Res<HandPhysicsConfig>,
+    last_good_pose: Res<LastGoodPose>,
+    mut tracked_hands: ResMut<TrackedHands>,
+) {
+    for hand in [Hand::Left, Hand::Right] {
+        let tracked = tracked_hands
+            .get(hand)
+            .map(|joints| joints.is_fully_tracked())
+            .unwrap_or(false);
+
+        if tracked {
+            continue;
+        }
+        if let Some(pose) = fallback_pose(hand, config.tracking_loss, &last_good_pose) {
+            *tracked_hands.get_mut(hand) = Some(pose);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::config::BoneBodyType;
+    use crate::constants::PhysicsHandsResource;
+    use crate::pose::{rest_pose, HandOrigin, HandPlacement};
+    use crate::provider::{OxrHandTargets, OxrJointQuery};
+    use crate::test_utils::{
+        bone_entity, bone_transform, drive, hand_app, move_joint_entities, spawn_joint_entities,
+        step, FixedTargets,
+    };
+
+    fn tracking_world(config: HandPhysicsConfig) -> (World, Schedule) {
+        let mut world = World::new();
+        spawn_joint_entities(
+            &mut world,
+            &get_default_hand(&Hand::Left),
+            &get_default_hand(&Hand::Right),
+        );
+        world.insert_resource(config);
+        world.init_resource::<TrackedHands>();
+        world.init_resource::<LastGoodPose>();
+        world.init_resource::<DrivenPose>();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(
+            (
+                collect_tracked_hands,
+                update_last_good_pose,
+                hold_pose_on_tracking_loss,
+            )
+                .chain(),
+        );
+        (world, schedule)
+    }
+
+    fn hide(world: &mut World, hand: Hand) {
+        let mut joints = world.query::<(&Hand, &mut Visibility)>();
+        for (joint_hand, mut visibility) in joints.iter_mut(world) {
+            if *joint_hand == hand {
+                *visibility = Visibility::Hidden;
+            }
+        }
+    }
+
+    fn sample(world: &mut World) -> OxrHandTargets {
+        world.run_system_once(
+            |hands_res: Res<HandsResource>,
+             joint_query: OxrJointQuery,
+             tracked_hands: Res<TrackedHands>,
+             config: Res<HandPhysicsConfig>| {
+                OxrHandTargets::sample(&hands_res, &joint_query, &tracked_hands, &config, 0.0)
+            },
+        )
+    }
+
+    #[test]
+    fn hidden_joints_are_lost() {
+        let (mut world, mut schedule) = tracking_world(HandPhysicsConfig::default());
+
+        hide(&mut world, Hand::Right);
+        schedule.run(&mut world);
+        let tracked_hands = world.resource::<TrackedHands>();
+        assert!(tracked_hands.left.unwrap().is_fully_tracked());
+        assert!(!tracked_hands
+            .right
+            .unwrap()
+            .inner
+            .iter()
+            .any(|joint| joint.position_tracked));
+
+        //by default a hand holding perfectly still is still tracked
+        for _ in 0..10 {
+            schedule.run(&mut world);
+        }
+        assert!(world
+            .resource::<TrackedHands>()
+            .left
+            .unwrap()
+            .is_fully_tracked());
+    }
+
+    #[test]
+    fn frozen_joints_are_lost_once_asked_for() {
+        let (mut world, mut schedule) = tracking_world(HandPhysicsConfig {
+            stale_joint_frames: 3,
+            ..default()
+        });
+
+        //seen once and then still for three frames
+        for _ in 0..3 {
+            schedule.run(&mut world);
+            assert!(world
+                .resource::<TrackedHands>()
+                .left
+                .unwrap()
+                .is_fully_tracked());
+        }
+        schedule.run(&mut world);
+        assert!(!world
+            .resource::<TrackedHands>()
+            .left
+            .unwrap()
+            .is_fully_tracked());
+
+        let moved =
+            get_default_hand(&Hand::Left).anchored_at_wrist(&Transform::from_xyz(0.1, 1.0, -0.3));
+        move_joint_entities(&mut world, Hand::Left, &moved);
+        schedule.run(&mut world);
+        assert!(world
+            .resource::<TrackedHands>()
+            .left
+            .unwrap()
+            .is_fully_tracked());
+    }
+
+    #[test]
+    fn held_pose_is_what_the_bones_aim_at() {
+        let lost_at = Transform::from_xyz(0.4, 0.8, -0.2);
+        let wrist = |targets: &OxrHandTargets| {
+            targets
+                .right
+                .unwrap()
+                .joint(PhysicsHandBone::Wrist)
+                .position
+        };
+
+        for (behavior, expected) in [
+            (
+                TrackingLossBehavior::HoldLastGoodPose,
+                get_default_hand(&Hand::Right)
+                    .joint(PhysicsHandBone::Wrist)
+                    .position,
+            ),
+            (TrackingLossBehavior::FollowSource, lost_at.translation),
+        ] {
+            let (mut world, mut schedule) = tracking_world(HandPhysicsConfig {
+                tracking_loss: behavior,
+                ..default()
+            });
+            schedule.run(&mut world);
+
+            //the hand drifts off while tracking reports it lost
+            let drifted = get_default_hand(&Hand::Right).anchored_at_wrist(&lost_at);
+            move_joint_entities(&mut world, Hand::Right, &drifted);
+            hide(&mut world, Hand::Right);
+            schedule.run(&mut world);
+
+            assert!(wrist(&sample(&mut world)).distance(expected) < 1e-5);
+        }
+    }
+
+    #[test]
+    fn accumulators_are_zeroed_on_resume() {
+        let (mut world, _) = tracking_world(HandPhysicsConfig::default());
+        world.init_resource::<Time>();
+        world.init_resource::<WristSmoothing>();
+        world.init_resource::<FingerSmoothing>();
+        world.init_resource::<Events<HandTrackingLost>>();
+        world.init_resource::<Events<HandTrackingStarted>>();
+        let bone = world
+            .spawn((
+                Hand::Right,
+                PhysicsHandBone::IndexTip,
+                Velocity::zero(),
+                BoneInitState::True,
+            ))
+            .id();
+        let tip = get_hand_resource_entity(
+            &world.resource::<HandsResource>().right,
+            PhysicsHandBone::IndexTip,
+        );
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(
+            (
+                collect_tracked_hands,
+                detect_tracking_transitions,
+                reset_accumulators_on_resume,
+            )
+                .chain(),
+        );
+        let dirty = |world: &mut World| {
+            world.resource_mut::<WristSmoothing>().right = Some(Quat::from_rotation_x(1.0));
+            world
+                .resource_mut::<FingerSmoothing>()
+                .filtered
+                .insert(tip, Transform::IDENTITY);
+            *world.get_mut::<Velocity>(bone).unwrap() = Velocity::linear(Vec3::X * 3.0);
+            *world.get_mut::<BoneInitState>(bone).unwrap() = BoneInitState::True;
+        };
+
+        schedule.run(&mut world);
+        dirty(&mut world);
+
+        //losing the hand keeps everything as it was
+        hide(&mut world, Hand::Right);
+        schedule.run(&mut world);
+        assert!(world.resource::<WristSmoothing>().right.is_some());
+        assert_eq!(world.get::<Velocity>(bone).unwrap().linvel, Vec3::X * 3.0);
+
+        let moved =
+            get_default_hand(&Hand::Right).anchored_at_wrist(&Transform::from_xyz(0.1, 1.0, -0.3));
+        move_joint_entities(&mut world, Hand::Right, &moved);
+        let mut joints = world.query::<(&Hand, &mut Visibility)>();
+        for (_, mut visibility) in joints.iter_mut(&mut world) {
+            *visibility = Visibility::Inherited;
+        }
+        schedule.run(&mut world);
+
+        assert!(world.resource::<WristSmoothing>().right.is_none());
+        assert!(!world
+            .resource::<FingerSmoothing>()
+            .filtered
+            .contains_key(&tip));
+        assert_eq!(world.get::<Velocity>(bone).unwrap().linvel, Vec3::ZERO);
+        assert!(*world.get::<BoneInitState>(bone).unwrap() == BoneInitState::False);
+    }
+
+    #[test]
+    fn physics_only_updates_while_a_hand_is_tracked() {
+        //every joint hidden, the hands are there but nothing is tracked
+        let (mut world, mut schedule) = tracking_world(HandPhysicsConfig::default());
+        hide(&mut world, Hand::Left);
+        hide(&mut world, Hand::Right);
+        schedule.run(&mut world);
+        assert!(!world.run_system_once(any_hand_tracked));
+        world.insert_resource(HandTargetSource::new(FixedTargets {
+            left: get_default_hand(&Hand::Left),
+            right: get_default_hand(&Hand::Right),
+        }));
+        assert!(world.run_system_once(any_hand_tracked));
+
+        //a kinematic body so the driven bone really moves, the palm isn't driven at all
+        let mut app = hand_app(HandPhysicsConfig {
+            body_type: BoneBodyType::KinematicVelocityBased,
+            ..default()
+        });
+        let bone = bone_entity(&app, Hand::Right, PhysicsHandBone::IndexProximal);
+        let initialized =
+            |app: &App| app.world.get::<BoneInitState>(bone) == Some(&BoneInitState::True);
+        step(&mut app, 3);
+        assert!(!app.world.run_system_once(any_hand_tracked));
+        assert!(!initialized(&app));
+
+        //one tracked hand is enough
+        let origin = *app.world.resource::<HandOrigin>();
+        let placement = *app.world.resource::<HandPlacement>();
+        let raised = rest_pose(Hand::Right, &origin, &placement)
+            .transformed(&HandOrigin(Transform::from_xyz(0.0, 0.1, 0.0)));
+        drive(&mut app, Hand::Right, raised);
+        let start = bone_transform(&app, Hand::Right, PhysicsHandBone::IndexProximal).translation;
+        step(&mut app, 10);
+        assert!(app.world.run_system_once(any_hand_tracked));
+        assert!(initialized(&app));
+        let moved = bone_transform(&app, Hand::Right, PhysicsHandBone::IndexProximal).translation;
+        assert!(moved.y > start.y);
+
+        //and losing it again leaves the bones alone
+        app.world.resource_mut::<DrivenPose>().right = None;
+        step(&mut app, 1);
+        assert!(!app.world.run_system_once(any_hand_tracked));
+        *app.world.get_mut::<BoneInitState>(bone).unwrap() = BoneInitState::False;
+        step(&mut app, 3);
+        assert!(!initialized(&app));
+    }
+
+    #[test]
+    fn physics_spawns_once_enough_joints_are_tracked() {
+        let mut app = hand_app(HandPhysicsConfig {
+            min_tracked_joints: 20,
+            tracking_loss: TrackingLossBehavior::HoldLastGoodPose,
+            ..default()
+        });
+        let rest = get_default_hand(&Hand::Right);
+        spawn_joint_entities(&mut app.world, &get_default_hand(&Hand::Left), &rest);
+        hide(&mut app.world, Hand::Left);
+        hide(&mut app.world, Hand::Right);
+        let hand_res = app.world.resource::<HandsResource>().right;
+
+        for shown in 0..=HAND_JOINT_COUNT {
+            if shown > 0 {
+                let bone = NameToHandJoint::get_physics_bone_from_index(shown - 1);
+                *app.world
+                    .get_mut::<Visibility>(get_hand_resource_entity(&hand_res, bone))
+                    .unwrap() = Visibility::Inherited;
+            }
+            //keep the hand moving like a live one
+            let moved = rest.transformed(&HandOrigin(Transform::from_xyz(
+                0.0,
+                shown as f32 * 0.001,
+                0.0,
+            )));
+            move_joint_entities(&mut app.world, Hand::Right, &moved);
+            step(&mut app, 1);
+
+            assert_eq!(
+                app.world.contains_resource::<PhysicsHandsResource>(),
+                shown >= 20,
+                "{} joints tracked",
+                shown
+            );
+        }
+    }
+}